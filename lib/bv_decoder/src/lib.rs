@@ -18,6 +18,11 @@ pub fn b64url_to_bitvec(input: &str) -> Result<BitVec, base64::DecodeError> {
   Ok(bitvec)
 }
 
+/// Minimum voted weight reaching the 2/3 consensus threshold of the eligible weight.
+pub fn quorum_threshold(eligible_weight: u64) -> u64 {
+  (eligible_weight * 2).div_ceil(3)
+}
+
 #[derive(Clone, Debug)]
 pub struct BvWeights {
   pub bv: BitVec,
@@ -57,6 +62,28 @@ impl BvWeights {
     }
     return voted;
   }
+
+  /// Voted weight above the consensus threshold, negative when the quorum was not met.
+  pub fn quorum_margin(&self) -> i64 {
+    (self.voted_weight() as i64) - (quorum_threshold(self.eligible_weight()) as i64)
+  }
+
+  pub fn quorum_met(&self) -> bool {
+    self.quorum_margin() >= 0
+  }
+
+  /// Whether the election member at `index` signed, the first member being the last bit of the bitvector.
+  pub fn signed(&self, index: usize) -> bool {
+    match self.bv.len().checked_sub(index + 1) {
+      Some(pos) => self.bv.get(pos).unwrap_or(false),
+      None => false,
+    }
+  }
+
+  /// Indices of the election members that signed.
+  pub fn signer_indices(&self) -> Vec<usize> {
+    (0..self.weights.len()).filter(|i| self.signed(*i)).collect()
+  }
 }
 
 #[cfg(test)]
@@ -255,10 +282,49 @@ mod tests {
     assert_eq!(bv_weights.bv, expected);
   }
 
+  #[test]
+  fn test_signers_testnet_epoch1000() {
+    let bv_weights = BvWeights::from_b64url("-9s", &vec![13, 13, 13, 6, 13, 13, 13, 10, 9, 9, 13, 10, 13, 9, 9, 9]).unwrap();
+    // bv: 1111101111011011, members 2, 5 and 10 did not sign
+    assert_eq!(bv_weights.signer_indices(), vec![0, 1, 3, 4, 6, 7, 8, 9, 11, 12, 13, 14, 15]);
+    assert!(bv_weights.signed(0));
+    assert!(!bv_weights.signed(2));
+    assert!(!bv_weights.signed(16));
+    let signed_weight: u64 = bv_weights
+      .signer_indices()
+      .iter()
+      .map(|i| bv_weights.weights[*i])
+      .sum();
+    assert_eq!(signed_weight, bv_weights.voted_weight());
+  }
+
+  #[test]
+  fn test_quorum() {
+    let bv_weights = BvWeights::from_b64url("-9s", &vec![13, 13, 13, 6, 13, 13, 13, 10, 9, 9, 13, 10, 13, 9, 9, 9]).unwrap();
+    // 136 voted of 175 eligible, threshold 117
+    assert_eq!(quorum_threshold(175), 117);
+    assert_eq!(bv_weights.quorum_margin(), 19);
+    assert!(bv_weights.quorum_met());
+
+    let mut bv = BitVec::from_elem(21, false);
+    for i in 7..21 {
+      bv.set(i, true);
+    }
+    // exactly 14 of 21 equally weighted members
+    let bv_weights = BvWeights::from_bitvec(bv, &vec![10; 21]);
+    assert_eq!(bv_weights.quorum_margin(), 0);
+    assert!(bv_weights.quorum_met());
+
+    let bv_weights = BvWeights::from_bitvec(BitVec::from_elem(21, false), &vec![10; 21]);
+    assert_eq!(bv_weights.quorum_margin(), -140);
+    assert!(!bv_weights.quorum_met());
+  }
+
   #[test]
   fn test_empty() {
     let bv_weights = BvWeights::from_b64url("", &vec![]).unwrap();
     assert_eq!(bv_weights.eligible_weight(), 0);
     assert_eq!(bv_weights.voted_weight(), 0);
+    assert!(bv_weights.signer_indices().is_empty());
   }
 }
@@ -0,0 +1,129 @@
+use k256::ecdsa::{ RecoveryId, Signature, VerifyingKey };
+use sha3::{ Digest, Keccak256 };
+use crate::SigError;
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+  Keccak256::digest(data).into()
+}
+
+/// Hash of a message signed with `personal_sign` as specified in EIP-191 (version 0x45).
+pub fn eip191_hash(message: &[u8]) -> [u8; 32] {
+  let mut prefixed = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+  prefixed.extend_from_slice(message);
+  keccak256(&prefixed)
+}
+
+/// Encode an address with the mixed-case checksum described in EIP-55.
+pub fn to_checksum_address(address: &[u8; 20]) -> String {
+  let lower = hex::encode(address);
+  let hash = keccak256(lower.as_bytes());
+  let mut result = String::from("0x");
+  for (i, c) in lower.chars().enumerate() {
+    let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+    if c.is_ascii_alphabetic() && nibble >= 8 {
+      result.push(c.to_ascii_uppercase());
+    } else {
+      result.push(c);
+    }
+  }
+  result
+}
+
+pub fn pubkey_to_address(key: &VerifyingKey) -> [u8; 20] {
+  let point = key.to_encoded_point(false);
+  // skip the 0x04 uncompressed point prefix
+  let hash = keccak256(&point.as_bytes()[1..]);
+  let mut address = [0u8; 20];
+  address.copy_from_slice(&hash[12..]);
+  address
+}
+
+/// Recover the checksummed address that produced a 65 byte `r || s || v` signature over an EIP-191 message.
+pub fn recover_address(message: &[u8], signature: &str) -> Result<String, SigError> {
+  let sig_bytes = hex::decode(signature.trim_start_matches("0x")).map_err(|_| SigError::InvalidHex)?;
+  if sig_bytes.len() != 65 {
+    return Err(SigError::InvalidLength);
+  }
+  // v may be encoded as 0/1 or 27/28
+  let v = match sig_bytes[64] {
+    0 | 1 => sig_bytes[64],
+    27 | 28 => sig_bytes[64] - 27,
+    _ => {
+      return Err(SigError::InvalidRecoveryId);
+    }
+  };
+  let sig = Signature::from_slice(&sig_bytes[..64]).map_err(|_| SigError::RecoveryFailed)?;
+  let recid = RecoveryId::from_byte(v).ok_or(SigError::InvalidRecoveryId)?;
+  let key = VerifyingKey::recover_from_prehash(&eip191_hash(message), &sig, recid).map_err(|_| SigError::RecoveryFailed)?;
+  Ok(to_checksum_address(&pubkey_to_address(&key)))
+}
+
+/// Verify a `personal_sign` signature against an address, the address comparison is case-insensitive.
+pub fn verify_personal_sign(message: &[u8], signature: &str, address: &str) -> bool {
+  match recover_address(message, signature) {
+    Ok(recovered) => recovered.eq_ignore_ascii_case(address),
+    Err(_) => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use k256::ecdsa::SigningKey;
+
+  fn test_key() -> SigningKey {
+    SigningKey::from_slice(&hex::decode("0123456789012345678901234567890123456789012345678901234567890123").unwrap()).unwrap()
+  }
+
+  fn personal_sign(key: &SigningKey, message: &[u8], v_offset: u8) -> String {
+    let (sig, recid) = key.sign_prehash_recoverable(&eip191_hash(message)).unwrap();
+    let mut bytes = sig.to_bytes().to_vec();
+    bytes.push(recid.to_byte() + v_offset);
+    format!("0x{}", hex::encode(bytes))
+  }
+
+  #[test]
+  fn test_keccak256_empty() {
+    assert_eq!(hex::encode(keccak256(b"")), "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470");
+  }
+
+  #[test]
+  fn test_address_from_key() {
+    let address = pubkey_to_address(test_key().verifying_key());
+    assert_eq!(to_checksum_address(&address), "0x14791697260E4c9A71f18484C9f997B308e59325");
+  }
+
+  #[test]
+  fn test_checksum_address() {
+    // test vectors from EIP-55
+    let addresses = [
+      "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+      "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+      "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+      "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+    for a in addresses {
+      let bytes: [u8; 20] = hex::decode(&a[2..]).unwrap().try_into().unwrap();
+      assert_eq!(to_checksum_address(&bytes), a);
+    }
+  }
+
+  #[test]
+  fn test_recover_address() {
+    let message = b"0x14791697260E4c9A71f18484C9f997B308e59325:vsc_cv_login:evm:deadbeef";
+    let sig = personal_sign(&test_key(), message, 27);
+    assert_eq!(recover_address(message, &sig).unwrap(), "0x14791697260E4c9A71f18484C9f997B308e59325");
+    assert!(verify_personal_sign(message, &sig, "0x14791697260e4c9a71f18484c9f997b308e59325"));
+    assert!(!verify_personal_sign(b"another message", &sig, "0x14791697260E4c9A71f18484C9f997B308e59325"));
+    // recovery id without the 27 offset
+    let sig = personal_sign(&test_key(), message, 0);
+    assert!(verify_personal_sign(message, &sig, "0x14791697260E4c9A71f18484C9f997B308e59325"));
+  }
+
+  #[test]
+  fn test_invalid_signature() {
+    assert_eq!(recover_address(b"a", "0xzz"), Err(SigError::InvalidHex));
+    assert_eq!(recover_address(b"a", "0x00"), Err(SigError::InvalidLength));
+    assert_eq!(recover_address(b"a", &format!("0x{}", "11".repeat(64) + "05")), Err(SigError::InvalidRecoveryId));
+  }
+}
@@ -0,0 +1,97 @@
+use k256::ecdsa::{ RecoveryId, Signature, VerifyingKey };
+use ripemd::Ripemd160;
+use sha2::{ Digest, Sha256 };
+use crate::SigError;
+
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+  Sha256::digest(data).into()
+}
+
+/// Encode a public key in the Graphene format, i.e. `STM` + base58(compressed key || ripemd160 checksum).
+pub fn pubkey_to_string(key: &VerifyingKey, prefix: &str) -> String {
+  let compressed = key.to_encoded_point(true);
+  let mut bytes = compressed.as_bytes().to_vec();
+  let checksum = Ripemd160::digest(&bytes);
+  bytes.extend_from_slice(&checksum[..4]);
+  format!("{}{}", prefix, bs58::encode(bytes).into_string())
+}
+
+/// Decode a Graphene public key string with a 3 character prefix, verifying its checksum.
+pub fn pubkey_from_string(key: &str) -> Result<VerifyingKey, SigError> {
+  if key.len() < 4 {
+    return Err(SigError::InvalidPublicKey);
+  }
+  let bytes = bs58::decode(&key[3..]).into_vec().map_err(|_| SigError::InvalidPublicKey)?;
+  if bytes.len() != 37 {
+    return Err(SigError::InvalidPublicKey);
+  }
+  let checksum = Ripemd160::digest(&bytes[..33]);
+  if checksum[..4] != bytes[33..] {
+    return Err(SigError::InvalidPublicKey);
+  }
+  VerifyingKey::from_sec1_bytes(&bytes[..33]).map_err(|_| SigError::InvalidPublicKey)
+}
+
+/// Recover the public key from a 65 byte hex encoded compact signature over a SHA-256 digest.
+pub fn recover_pubkey(digest: &[u8; 32], signature: &str) -> Result<VerifyingKey, SigError> {
+  let sig_bytes = hex::decode(signature).map_err(|_| SigError::InvalidHex)?;
+  if sig_bytes.len() != 65 {
+    return Err(SigError::InvalidLength);
+  }
+  // header byte is 27 + 4 (compressed) + recovery id
+  if sig_bytes[0] < 27 || sig_bytes[0] > 34 {
+    return Err(SigError::InvalidRecoveryId);
+  }
+  let recid = RecoveryId::from_byte((sig_bytes[0] - 27) & 3).ok_or(SigError::InvalidRecoveryId)?;
+  let sig = Signature::from_slice(&sig_bytes[1..]).map_err(|_| SigError::RecoveryFailed)?;
+  VerifyingKey::recover_from_prehash(digest, &sig, recid).map_err(|_| SigError::RecoveryFailed)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use k256::ecdsa::SigningKey;
+
+  fn test_key() -> SigningKey {
+    SigningKey::from_slice(&sha256(b"alice-posting-password")).unwrap()
+  }
+
+  fn sign_compact(key: &SigningKey, digest: &[u8; 32]) -> String {
+    let (sig, recid) = key.sign_prehash_recoverable(digest).unwrap();
+    let mut bytes = vec![recid.to_byte() + 31];
+    bytes.extend_from_slice(&sig.to_bytes());
+    hex::encode(bytes)
+  }
+
+  #[test]
+  fn test_pubkey_string_roundtrip() {
+    let key = test_key();
+    let encoded = pubkey_to_string(key.verifying_key(), "STM");
+    assert!(encoded.starts_with("STM"));
+    assert_eq!(encoded.len(), 53);
+    assert_eq!(&pubkey_from_string(&encoded).unwrap(), key.verifying_key());
+    // corrupted checksum
+    let mut corrupted = encoded.clone();
+    corrupted.pop();
+    corrupted.push(if encoded.ends_with('1') { '2' } else { '1' });
+    assert_eq!(pubkey_from_string(&corrupted), Err(SigError::InvalidPublicKey));
+  }
+
+  #[test]
+  fn test_recover_pubkey() {
+    let key = test_key();
+    let digest = sha256(b"alice:vsc_cv_login:hive:1000:0003e8abcdef:deadbeef");
+    let sig = sign_compact(&key, &digest);
+    assert_eq!(&recover_pubkey(&digest, &sig).unwrap(), key.verifying_key());
+    let other = sha256(b"bob:vsc_cv_login:hive:1000:0003e8abcdef:deadbeef");
+    assert_ne!(&recover_pubkey(&other, &sig).unwrap(), key.verifying_key());
+  }
+
+  #[test]
+  fn test_invalid_signature() {
+    let digest = sha256(b"a");
+    assert_eq!(recover_pubkey(&digest, "zz"), Err(SigError::InvalidHex));
+    assert_eq!(recover_pubkey(&digest, "1f00"), Err(SigError::InvalidLength));
+    assert_eq!(recover_pubkey(&digest, &("00".to_string() + &"11".repeat(64))), Err(SigError::InvalidRecoveryId));
+  }
+}
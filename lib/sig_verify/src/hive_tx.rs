@@ -0,0 +1,205 @@
+use k256::{ ecdsa::{ hazmat::SignPrimitive, SigningKey }, FieldBytes };
+use sha2::Sha256;
+pub use k256::ecdsa::SigningKey as PrivateKey;
+use crate::{ hive::sha256, SigError };
+
+/// Hive mainnet chain id
+pub const HIVE_CHAIN_ID: [u8; 32] = [
+  0xbe, 0xea, 0xb0, 0xde, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+];
+
+const CUSTOM_JSON_OP_ID: u64 = 18;
+
+/// Decode a WIF encoded private key, i.e. base58(0x80 || key || first 4 bytes of double SHA-256 checksum).
+pub fn wif_to_key(wif: &str) -> Result<SigningKey, SigError> {
+  let bytes = bs58::decode(wif).into_vec().map_err(|_| SigError::InvalidPrivateKey)?;
+  if bytes.len() != 37 || bytes[0] != 0x80 {
+    return Err(SigError::InvalidPrivateKey);
+  }
+  let checksum = sha256(&sha256(&bytes[..33]));
+  if checksum[..4] != bytes[33..] {
+    return Err(SigError::InvalidPrivateKey);
+  }
+  SigningKey::from_slice(&bytes[1..33]).map_err(|_| SigError::InvalidPrivateKey)
+}
+
+/// Graphene nodes only accept signatures where neither r nor s have a leading zero byte or the high bit set.
+fn is_canonical(sig: &[u8; 64]) -> bool {
+  sig[0] & 0x80 == 0 && !(sig[0] == 0 && sig[1] & 0x80 == 0) && sig[32] & 0x80 == 0 && !(sig[32] == 0 && sig[33] & 0x80 == 0)
+}
+
+/// Sign a digest into a hex encoded 65 byte compact signature, retrying with additional RFC6979 data until canonical.
+pub fn sign_canonical(key: &SigningKey, digest: &[u8; 32]) -> Result<String, SigError> {
+  let z = FieldBytes::from(*digest);
+  for attempt in 0u32.. {
+    let ad = if attempt == 0 { vec![] } else { sha256(&[digest.as_slice(), &attempt.to_le_bytes()].concat()).to_vec() };
+    let (sig, recid) = key
+      .as_nonzero_scalar()
+      .try_sign_prehashed_rfc6979::<Sha256>(&z, &ad)
+      .map_err(|_| SigError::SigningFailed)?;
+    let recid = recid.ok_or(SigError::SigningFailed)?;
+    let compact: [u8; 64] = sig.to_bytes().into();
+    if is_canonical(&compact) {
+      let mut bytes = vec![recid.to_byte() + 31];
+      bytes.extend_from_slice(&compact);
+      return Ok(hex::encode(bytes));
+    }
+  }
+  Err(SigError::SigningFailed)
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut n: u64) {
+  loop {
+    let byte = (n & 0x7f) as u8;
+    n >>= 7;
+    if n == 0 {
+      buf.push(byte);
+      break;
+    }
+    buf.push(byte | 0x80);
+  }
+}
+
+fn write_string(buf: &mut Vec<u8>, s: &str) {
+  write_varint(buf, s.len() as u64);
+  buf.extend_from_slice(s.as_bytes());
+}
+
+fn write_string_array(buf: &mut Vec<u8>, arr: &[String]) {
+  write_varint(buf, arr.len() as u64);
+  for s in arr {
+    write_string(buf, s);
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct CustomJson {
+  pub required_auths: Vec<String>,
+  pub required_posting_auths: Vec<String>,
+  pub id: String,
+  pub json: String,
+}
+
+/// Hive transaction containing `custom_json` operations.
+#[derive(Clone, Debug)]
+pub struct Transaction {
+  pub ref_block_num: u16,
+  pub ref_block_prefix: u32,
+  /// Expiration unix timestamp in seconds
+  pub expiration: u32,
+  pub operations: Vec<CustomJson>,
+}
+
+impl Transaction {
+  /// Reference the head block by its hex encoded block id.
+  pub fn new(head_block_id: &str, expiration: u32, operations: Vec<CustomJson>) -> Result<Self, SigError> {
+    let id = hex::decode(head_block_id).map_err(|_| SigError::InvalidHex)?;
+    if id.len() != 20 {
+      return Err(SigError::InvalidLength);
+    }
+    Ok(Transaction {
+      ref_block_num: (u32::from_be_bytes([id[0], id[1], id[2], id[3]]) & 0xffff) as u16,
+      ref_block_prefix: u32::from_le_bytes([id[4], id[5], id[6], id[7]]),
+      expiration,
+      operations,
+    })
+  }
+
+  /// Binary serialization without signatures
+  pub fn serialize(&self) -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&self.ref_block_num.to_le_bytes());
+    buf.extend_from_slice(&self.ref_block_prefix.to_le_bytes());
+    buf.extend_from_slice(&self.expiration.to_le_bytes());
+    write_varint(&mut buf, self.operations.len() as u64);
+    for op in self.operations.iter() {
+      write_varint(&mut buf, CUSTOM_JSON_OP_ID);
+      write_string_array(&mut buf, &op.required_auths);
+      write_string_array(&mut buf, &op.required_posting_auths);
+      write_string(&mut buf, &op.id);
+      write_string(&mut buf, &op.json);
+    }
+    // extensions
+    write_varint(&mut buf, 0);
+    buf
+  }
+
+  /// Transaction id, the first 20 bytes of the SHA-256 of the serialized transaction.
+  pub fn id(&self) -> String {
+    hex::encode(&sha256(&self.serialize())[..20])
+  }
+
+  pub fn digest(&self, chain_id: &[u8; 32]) -> [u8; 32] {
+    let mut data = chain_id.to_vec();
+    data.extend(self.serialize());
+    sha256(&data)
+  }
+
+  pub fn sign(&self, key: &SigningKey, chain_id: &[u8; 32]) -> Result<String, SigError> {
+    sign_canonical(key, &self.digest(chain_id))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::hive::{ pubkey_to_string, recover_pubkey };
+
+  fn to_wif(key: &SigningKey) -> String {
+    let mut bytes = vec![0x80];
+    bytes.extend_from_slice(&key.to_bytes());
+    let checksum = sha256(&sha256(&bytes));
+    bytes.extend_from_slice(&checksum[..4]);
+    bs58::encode(bytes).into_string()
+  }
+
+  fn test_tx() -> Transaction {
+    Transaction::new("05f5e0ffa1b2c3d4e5f60718293a4b5c6d7e8f90", 1700000000, vec![CustomJson {
+      required_auths: vec![],
+      required_posting_auths: vec![String::from("alice")],
+      id: String::from("vsc_cv_attest"),
+      json: String::from("{}"),
+    }]).unwrap()
+  }
+
+  #[test]
+  fn test_wif_roundtrip() {
+    let key = SigningKey::from_slice(&sha256(b"alice-posting-password")).unwrap();
+    let wif = to_wif(&key);
+    assert!(wif.starts_with('5'));
+    assert_eq!(wif_to_key(&wif).unwrap().to_bytes(), key.to_bytes());
+    let mut corrupted = wif.clone();
+    corrupted.pop();
+    corrupted.push(if wif.ends_with('1') { '2' } else { '1' });
+    assert_eq!(wif_to_key(&corrupted).err(), Some(SigError::InvalidPrivateKey));
+  }
+
+  #[test]
+  fn test_serialize() {
+    let tx = test_tx();
+    assert_eq!(tx.ref_block_num, 0xe0ff);
+    assert_eq!(tx.ref_block_prefix, 0xd4c3b2a1);
+    assert_eq!(
+      hex::encode(tx.serialize()),
+      "ffe0a1b2c3d400f153650112000105616c6963650d7673635f63765f617474657374027b7d00"
+    );
+    assert_eq!(tx.id().len(), 40);
+  }
+
+  #[test]
+  fn test_sign_canonical() {
+    let key = SigningKey::from_slice(&sha256(b"alice-posting-password")).unwrap();
+    let tx = test_tx();
+    let digest = tx.digest(&HIVE_CHAIN_ID);
+    for i in 0u8..16 {
+      let digest = sha256(&[digest.as_slice(), &[i]].concat());
+      let sig = sign_canonical(&key, &digest).unwrap();
+      let bytes: [u8; 65] = hex::decode(&sig).unwrap().try_into().unwrap();
+      assert!(is_canonical(bytes[1..].try_into().unwrap()));
+      assert_eq!(
+        pubkey_to_string(&recover_pubkey(&digest, &sig).unwrap(), "STM"),
+        pubkey_to_string(key.verifying_key(), "STM")
+      );
+    }
+  }
+}
@@ -0,0 +1,32 @@
+use std::fmt;
+
+pub mod evm;
+pub mod hive;
+pub mod hive_tx;
+
+#[derive(Debug, PartialEq)]
+pub enum SigError {
+  InvalidHex,
+  InvalidLength,
+  InvalidRecoveryId,
+  RecoveryFailed,
+  InvalidPublicKey,
+  InvalidPrivateKey,
+  SigningFailed,
+}
+
+impl fmt::Display for SigError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      SigError::InvalidHex => write!(f, "signature is not valid hex"),
+      SigError::InvalidLength => write!(f, "signature must be 65 bytes"),
+      SigError::InvalidRecoveryId => write!(f, "invalid recovery id"),
+      SigError::RecoveryFailed => write!(f, "failed to recover public key from signature"),
+      SigError::InvalidPublicKey => write!(f, "invalid public key"),
+      SigError::InvalidPrivateKey => write!(f, "invalid private key"),
+      SigError::SigningFailed => write!(f, "failed to sign digest"),
+    }
+  }
+}
+
+impl std::error::Error for SigError {}
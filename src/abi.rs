@@ -0,0 +1,284 @@
+use serde::{ Serialize, Deserialize };
+use utoipa::ToSchema;
+
+pub const ABI_VERSION: u8 = 1;
+
+/// Typed ABI of a verified contract, generated from the `.d.ts` of the debug build.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct Abi {
+  pub version: u8,
+  pub functions: Vec<AbiFunction>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct AbiFunction {
+  pub name: String,
+  pub params: Vec<AbiParam>,
+  pub returns: AbiType,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct AbiParam {
+  pub name: String,
+  #[serde(flatten)]
+  pub ty: AbiType,
+  pub optional: bool,
+  /// Whether the parameter is parsed as JSON by the contract
+  pub json: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct AbiType {
+  /// TypeScript type of the generated bindings
+  #[serde(rename = "type")]
+  pub ts_type: String,
+  /// AssemblyScript type from the bindings doc comment, i.e. `i32` or `~lib/string/String`
+  pub as_type: Option<String>,
+}
+
+/// Split by commas that are not nested within brackets.
+fn split_top_level(s: &str) -> Vec<&str> {
+  let mut parts = Vec::new();
+  let mut depth = 0i32;
+  let mut start = 0;
+  for (i, c) in s.char_indices() {
+    match c {
+      '<' | '(' | '[' | '{' => {
+        depth += 1;
+      }
+      '>' | ')' | ']' | '}' => {
+        depth -= 1;
+      }
+      ',' if depth == 0 => {
+        parts.push(&s[start..i]);
+        start = i + 1;
+      }
+      _ => {}
+    }
+  }
+  parts.push(&s[start..]);
+  parts
+    .into_iter()
+    .map(|p| p.trim())
+    .filter(|p| !p.is_empty())
+    .collect()
+}
+
+/// Position of the bracket closing the one opened right before `start`.
+fn find_closing(s: &str, start: usize, open: char, close: char) -> Option<usize> {
+  let mut depth = 1;
+  for (i, c) in s[start..].char_indices() {
+    if c == open {
+      depth += 1;
+    } else if c == close {
+      depth -= 1;
+      if depth == 0 {
+        return Some(start + i);
+      }
+    }
+  }
+  None
+}
+
+/// AssemblyScript types of parameters and the return value listed in a bindings doc comment.
+fn parse_doc_types(doc: &str) -> (Vec<(String, String)>, Option<String>) {
+  let mut params = Vec::new();
+  let mut returns = None;
+  for line in doc.lines() {
+    let line = line.trim().trim_start_matches('*').trim();
+    let ty = line.split('`').nth(1).map(String::from);
+    if let Some(rest) = line.strip_prefix("@param ") {
+      if let (Some(name), Some(ty)) = (rest.split_whitespace().next(), ty) {
+        params.push((name.to_string(), ty));
+      }
+    } else if line.starts_with("@returns") {
+      returns = ty;
+    }
+  }
+  (params, returns)
+}
+
+/// Parse the exported functions declared in the generated `.d.ts` of a contract. Runtime exports are skipped.
+pub fn parse_dts(dts: &str) -> Abi {
+  let mut functions: Vec<AbiFunction> = Vec::new();
+  let mut doc = "";
+  let mut pos = 0;
+  while pos < dts.len() {
+    let rest = &dts[pos..];
+    // doc comments are indented within `declare namespace __AdaptedExports` of raw bindings
+    let comment = rest.trim_start();
+    if comment.starts_with("/**") {
+      let end = comment.find("*/").map(|e| e + 2).unwrap_or(comment.len());
+      doc = &comment[..end];
+      pos += rest.len() - comment.len() + end;
+      continue;
+    }
+    let line_end = rest.find('\n').map(|e| e + 1).unwrap_or(rest.len());
+    let line = rest[..line_end].trim();
+    if line.trim_start_matches("export ").trim_start_matches("declare ").starts_with("function ") {
+      let signature = &rest[rest.find("function ").unwrap() + 9..];
+      if let Some(open) = signature.find('(') {
+        let name = signature[..open].trim().to_string();
+        let is_runtime = name.starts_with("__") || name == "instantiate";
+        if let Some(close) = find_closing(signature, open + 1, '(', ')').filter(|_| !is_runtime) {
+          let (doc_params, doc_returns) = parse_doc_types(doc);
+          let params = split_top_level(&signature[open + 1..close])
+            .into_iter()
+            .map(|p| {
+              let (pname, ptype) = p.split_once(':').unwrap_or((p, "any"));
+              let optional = pname.trim().ends_with('?');
+              let pname = pname.trim().trim_end_matches('?').to_string();
+              let as_type = doc_params
+                .iter()
+                .find(|(n, _)| n == &pname)
+                .map(|(_, t)| t.clone());
+              AbiParam {
+                name: pname,
+                ty: AbiType { ts_type: ptype.trim().to_string(), as_type },
+                optional,
+                json: false,
+              }
+            })
+            .collect();
+          let ret = signature[close + 1..].trim_start().strip_prefix(':').unwrap_or("void");
+          let ret = ret[..ret.find(';').unwrap_or(ret.len())].trim();
+          functions.push(AbiFunction {
+            name,
+            params,
+            returns: AbiType { ts_type: ret.to_string(), as_type: doc_returns },
+          });
+        }
+      }
+      doc = "";
+    } else if !line.is_empty() {
+      doc = "";
+    }
+    pos += line_end;
+  }
+  Abi { version: ABI_VERSION, functions }
+}
+
+/// Whether `JSON.parse(name)`, `JSON.parse<T>(name)` or `parse<T>(name)` occurs in whitespace-free code.
+fn parses_json(code: &str, name: &str) -> bool {
+  let arg = format!("({})", name);
+  code.match_indices("parse").any(|(i, m)| {
+    let mut rest = &code[i + m.len()..];
+    if let Some(generic) = rest.strip_prefix('<') {
+      match find_closing(generic, 0, '<', '>') {
+        Some(close) => {
+          rest = &generic[close + 1..];
+        }
+        None => {
+          return false;
+        }
+      }
+    }
+    rest.starts_with(&arg)
+  })
+}
+
+impl Abi {
+  /// Mark parameters that are passed to `JSON.parse` (or `parse<T>` of json-as) within the body of the
+  /// exported function in the verified sources. This is a textual heuristic and does not follow re-exports.
+  pub fn detect_json_params(&mut self, sources: &[&str]) {
+    for f in self.functions.iter_mut() {
+      let needle = format!("export function {}", f.name);
+      let body = sources.iter().find_map(|src| {
+        let mut from = 0;
+        while let Some(idx) = src[from..].find(&needle) {
+          let start = from + idx + needle.len();
+          // make sure the whole export name matched
+          if src[start..].trim_start().starts_with(['(', '<']) {
+            let open = src[start..].find('(').map(|o| start + o + 1)?;
+            let close = find_closing(src, open, '(', ')')?;
+            let body_open = src[close..].find('{').map(|b| close + b + 1)?;
+            let body_close = find_closing(src, body_open, '{', '}')?;
+            return Some(&src[body_open..body_close]);
+          }
+          from = start;
+        }
+        None
+      });
+      if let Some(body) = body {
+        let compact: String = body.chars().filter(|c| !c.is_whitespace()).collect();
+        for p in f.params.iter_mut() {
+          p.json = parses_json(&compact, &p.name);
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const DTS: &str = include_str!("../tests/fixtures/debug.d.ts");
+  const SOURCE: &str = include_str!("../tests/fixtures/index.ts");
+
+  fn types(f: &AbiFunction) -> Vec<(&str, &str, Option<&str>, bool)> {
+    f.params
+      .iter()
+      .map(|p| (p.name.as_str(), p.ty.ts_type.as_str(), p.ty.as_type.as_deref(), p.optional))
+      .collect()
+  }
+
+  #[test]
+  fn test_parse_dts() {
+    let abi = parse_dts(DTS);
+    assert_eq!(abi.version, ABI_VERSION);
+    let names: Vec<&str> = abi.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, vec!["init", "transfer", "mint", "balances", "pause"]);
+
+    let transfer = &abi.functions[1];
+    assert_eq!(types(transfer), vec![("payload", "string", Some("~lib/string/String"), false)]);
+    assert_eq!(transfer.returns.ts_type, "string");
+    assert_eq!(transfer.returns.as_type.as_deref(), Some("~lib/string/String"));
+
+    let mint = &abi.functions[2];
+    assert_eq!(types(mint), vec![
+      ("to", "string", Some("~lib/string/String"), false),
+      ("amount", "bigint", Some("i64"), false),
+      ("memo", "string", Some("~lib/string/String"), true)
+    ]);
+    assert_eq!(mint.returns.ts_type, "boolean");
+    assert_eq!(mint.returns.as_type.as_deref(), Some("bool"));
+
+    let balances = &abi.functions[3];
+    assert_eq!(types(balances), vec![("accounts", "Array<string>", Some("~lib/array/Array<~lib/string/String>"), false)]);
+    assert_eq!(balances.returns.ts_type, "Map<string, bigint>");
+    assert_eq!(balances.returns.as_type.as_deref(), Some("~lib/map/Map<~lib/string/String,i64>"));
+
+    let pause = &abi.functions[4];
+    assert!(pause.params.is_empty());
+    assert_eq!(pause.returns.ts_type, "void");
+    assert_eq!(pause.returns.as_type, None);
+  }
+
+  #[test]
+  fn test_parse_dts_unindented() {
+    let dts = "/**\n * assembly/index/add\n * @param a `i32`\n * @returns `i32`\n */\nexport declare function add(a: number): number;\n";
+    let abi = parse_dts(dts);
+    assert_eq!(abi.functions.len(), 1);
+    assert_eq!(types(&abi.functions[0]), vec![("a", "number", Some("i32"), false)]);
+    assert_eq!(abi.functions[0].returns.as_type.as_deref(), Some("i32"));
+  }
+
+  #[test]
+  fn test_detect_json_params() {
+    let mut abi = parse_dts(DTS);
+    abi.detect_json_params(&[SOURCE]);
+    let json: Vec<(&str, bool)> = abi.functions
+      .iter()
+      .flat_map(|f| f.params.iter().map(|p| (p.name.as_str(), p.json)))
+      .collect();
+    assert_eq!(json, vec![
+      ("owner", false),
+      ("payload", true),
+      ("to", false),
+      ("amount", false),
+      ("memo", true),
+      ("accounts", false)
+    ]);
+  }
+}
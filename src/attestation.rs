@@ -0,0 +1,314 @@
+use chrono::{ NaiveDateTime, Utc };
+use serde_json::{ json, Value };
+use tokio::time::{ sleep, Duration };
+use tokio_postgres::types::Type;
+use log::{ error, info };
+use std::{ process, sync::Arc };
+use sig_verify::hive_tx::{ wif_to_key, CustomJson, PrivateKey, Transaction, HIVE_CHAIN_ID };
+use crate::{ config::{ config, AttestationConf }, db::DbPool, types::hive::JsonRpcResp };
+
+/// Seconds until broadcasted attestation transactions expire
+const TX_EXPIRATION: i64 = 60;
+const POLL_INTERVAL: u64 = 30;
+const RPC_TIMEOUT: u64 = 15;
+
+/// Broadcasts a `custom_json` attestation for every successful verification with the configured posting key.
+#[derive(Clone)]
+pub struct AttestationPublisher {
+  db: DbPool,
+  http_client: reqwest::Client,
+  rpc_url: String,
+  conf: &'static AttestationConf,
+  key: Arc<PrivateKey>,
+}
+
+impl AttestationPublisher {
+  pub fn init(db: &DbPool, http_client: reqwest::Client, conf: &'static AttestationConf) -> Self {
+    let key = match wif_to_key(&conf.posting_key) {
+      Ok(k) => k,
+      Err(e) => {
+        error!("Failed to load attestation posting key: {}", e);
+        process::exit(1)
+      }
+    };
+    AttestationPublisher { db: db.clone(), http_client, rpc_url: config.hive_rpc.clone(), conf, key: Arc::new(key) }
+  }
+
+  pub fn start(&self) {
+    let publisher = self.clone();
+    tokio::spawn(async move {
+      info!("Begin publishing verification attestations as {}", publisher.conf().username);
+      loop {
+        if let Err(e) = publisher.publish_pending().await {
+          error!("Failed to publish attestations: {}", e);
+        }
+        sleep(Duration::from_secs(POLL_INTERVAL)).await;
+      }
+    });
+  }
+
+  fn conf(&self) -> &AttestationConf {
+    self.conf
+  }
+
+  async fn rpc_call(&self, method: &str, params: Value) -> Result<Value, String> {
+    let resp = self.http_client
+      .post(&self.rpc_url)
+      .json(&json!({ "id": 1, "jsonrpc": "2.0", "method": method, "params": params }))
+      .timeout(Duration::from_secs(RPC_TIMEOUT))
+      .send().await
+      .map_err(|e| e.to_string())?
+      .json::<JsonRpcResp>().await
+      .map_err(|e| e.to_string())?;
+    if let Some(e) = resp.error {
+      return Err(e["message"].as_str().map(String::from).unwrap_or(e.to_string()));
+    }
+    resp.result.ok_or(String::from("Missing result in RPC response"))
+  }
+
+  async fn publish_pending(&self) -> Result<(), String> {
+    let max_attempts = self.conf().max_attempts.unwrap_or(5) as i16;
+    let pending = self.db
+      .query(
+        "SELECT a.contract_addr, c.bytecode_cid, c.verified_ts, r.statement, r.signature, r.key_id, a.tx_id FROM vsc_cv.attestations a JOIN vsc_cv.contracts c ON c.contract_addr = a.contract_addr LEFT JOIN vsc_cv.receipts r ON r.contract_addr = a.contract_addr WHERE a.status=0::SMALLINT AND a.attempts < $1 ORDER BY a.created_ts ASC LIMIT 10;",
+        &[(&max_attempts, Type::INT2)]
+      ).await
+      .map_err(|e| e.to_string())?;
+    for p in pending.iter() {
+      let addr: &str = p.get(0);
+      // the node may have accepted the previous attempt without us receiving the response
+      if let Some(prev_tx_id) = p.get::<usize, Option<&str>>(6) {
+        match self.is_known(prev_tx_id).await {
+          Ok(true) => {
+            info!("Found previously broadcasted attestation for {} in tx {}", addr, prev_tx_id);
+            self.db
+              .query(
+                "UPDATE vsc_cv.attestations SET status=1::SMALLINT, last_error=NULL, broadcast_ts=$2 WHERE contract_addr=$1;",
+                &[
+                  (&addr, Type::VARCHAR),
+                  (&Utc::now().naive_utc(), Type::TIMESTAMP),
+                ]
+              ).await
+              .map_err(|e| e.to_string())?;
+            continue;
+          }
+          Ok(false) => {}
+          Err(e) => {
+            error!("Failed to look up previous attestation tx {} for {}: {}", prev_tx_id, addr, e);
+            // rebroadcasting could publish the attestation twice, so count this as a failed attempt instead
+            self.record_failure(addr, &e, Some(prev_tx_id), max_attempts).await?;
+            continue;
+          }
+        }
+      }
+      let payload = match p.get::<usize, Option<&str>>(3) {
+        Some(statement) =>
+          json!({
+          "statement": serde_json::from_str::<Value>(statement).map_err(|e| e.to_string())?,
+          "signature": p.get::<usize, Option<&str>>(4),
+          "key_id": p.get::<usize, Option<&str>>(5)
+        }),
+        None =>
+          json!({
+          "statement": {
+            "contract_addr": addr,
+            "bytecode_cid": p.get::<usize, &str>(1),
+            "verified_ts": p.get::<usize, Option<NaiveDateTime>>(2).map(|t| t.format("%Y-%m-%dT%H:%M:%S%.6f").to_string())
+          }
+        }),
+      };
+      let (tx_id, result) = match self.sign(&payload.to_string()).await {
+        Ok((tx_id, tx)) => (Some(tx_id), self.rpc_call("condenser_api.broadcast_transaction", json!([tx])).await),
+        Err(e) => (None, Err(e)),
+      };
+      match result {
+        Ok(_) => {
+          info!("Published attestation for {} in tx {}", addr, tx_id.as_deref().unwrap_or_default());
+          self.db
+            .query(
+              "UPDATE vsc_cv.attestations SET status=1::SMALLINT, tx_id=$2, attempts=attempts+1, last_error=NULL, broadcast_ts=$3 WHERE contract_addr=$1;",
+              &[
+                (&addr, Type::VARCHAR),
+                (&tx_id, Type::VARCHAR),
+                (&Utc::now().naive_utc(), Type::TIMESTAMP),
+              ]
+            ).await
+            .map_err(|e| e.to_string())?;
+        }
+        Err(e) => {
+          error!("Failed to broadcast attestation for {}: {}", addr, e);
+          // keep the id of the attempted transaction to look it up before the next attempt
+          self.record_failure(addr, &e, tx_id.as_deref(), max_attempts).await?;
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// Count a failed attempt, giving up on the attestation once `max_attempts` is reached.
+  async fn record_failure(&self, addr: &str, error: &str, tx_id: Option<&str>, max_attempts: i16) -> Result<(), String> {
+    self.db
+      .query(
+        "UPDATE vsc_cv.attestations SET status=CASE WHEN attempts+1 >= $3 THEN 2::SMALLINT ELSE 0::SMALLINT END, tx_id=$4, attempts=attempts+1, last_error=$2 WHERE contract_addr=$1;",
+        &[
+          (&addr, Type::VARCHAR),
+          (&error, Type::VARCHAR),
+          (&max_attempts, Type::INT2),
+          (&tx_id, Type::VARCHAR),
+        ]
+      ).await
+      .map_err(|e| e.to_string())?;
+    Ok(())
+  }
+
+  /// Whether a transaction is in the mempool or a block of the RPC node.
+  async fn is_known(&self, tx_id: &str) -> Result<bool, String> {
+    let result = self.rpc_call("transaction_status_api.find_transaction", json!({ "transaction_id": tx_id })).await?;
+    match result["status"].as_str() {
+      Some("within_mempool" | "within_reversible_block" | "within_irreversible_block") => Ok(true),
+      Some(_) => Ok(false),
+      None => Err(String::from("Missing transaction status")),
+    }
+  }
+
+  /// Sign a custom_json transaction referencing the current head block, returns the transaction id and the signed transaction.
+  async fn sign(&self, payload: &str) -> Result<(String, Value), String> {
+    let conf = self.conf();
+    let dgp = self.rpc_call("condenser_api.get_dynamic_global_properties", json!([])).await?;
+    let head_block_id = dgp["head_block_id"].as_str().ok_or("Missing head_block_id")?;
+    let head_time = NaiveDateTime::parse_from_str(dgp["time"].as_str().ok_or("Missing head block time")?, "%Y-%m-%dT%H:%M:%S").map_err(
+      |e| e.to_string()
+    )?;
+    let expiration = head_time + chrono::Duration::seconds(TX_EXPIRATION);
+    let op = CustomJson {
+      required_auths: vec![],
+      required_posting_auths: vec![conf.username.clone()],
+      id: conf.id.clone().unwrap_or(String::from("vsc_cv_attest")),
+      json: payload.to_string(),
+    };
+    let tx = Transaction::new(head_block_id, expiration.and_utc().timestamp() as u32, vec![op.clone()]).map_err(|e| e.to_string())?;
+    let signature = tx.sign(&self.key, &HIVE_CHAIN_ID).map_err(|e| e.to_string())?;
+    let tx_json =
+      json!({
+      "ref_block_num": tx.ref_block_num,
+      "ref_block_prefix": tx.ref_block_prefix,
+      "expiration": expiration.format("%Y-%m-%dT%H:%M:%S").to_string(),
+      "operations": [["custom_json", {
+        "required_auths": op.required_auths,
+        "required_posting_auths": op.required_posting_auths,
+        "id": op.id,
+        "json": op.json
+      }]],
+      "extensions": [],
+      "signatures": [signature]
+    });
+    Ok((tx.id(), tx_json))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use actix_web::{ post, web, App, HttpResponse, HttpServer };
+  use sig_verify::hive::recover_pubkey;
+  use std::{ collections::HashMap, sync::Mutex };
+
+  const HEAD_BLOCK_ID: &str = "05f5e0ffa1b2c3d4e5f60718293a4b5c6d7e8f90";
+
+  /// Transaction status by id, unknown ids are reported as `unknown`
+  type Statuses = web::Data<Mutex<HashMap<String, String>>>;
+
+  #[post("/")]
+  async fn mock_rpc(req: web::Json<Value>, statuses: Statuses) -> HttpResponse {
+    let result = match req["method"].as_str().unwrap_or_default() {
+      "condenser_api.get_dynamic_global_properties" => json!({ "head_block_id": HEAD_BLOCK_ID, "time": "2026-10-18T12:00:00" }),
+      "transaction_status_api.find_transaction" => {
+        let id = req["params"]["transaction_id"].as_str().unwrap_or_default();
+        match statuses.lock().unwrap().get(id) {
+          Some(s) if s == "missing" => json!({}),
+          Some(s) => json!({ "status": s }),
+          None => json!({ "status": "unknown" }),
+        }
+      }
+      method => {
+        return HttpResponse::Ok().json(json!({ "id": 1, "jsonrpc": "2.0", "error": { "code": -32601, "message": format!("Could not find method {}", method) } }));
+      }
+    };
+    HttpResponse::Ok().json(json!({ "id": 1, "jsonrpc": "2.0", "result": result }))
+  }
+
+  async fn publisher(statuses: &[(&str, &str)]) -> AttestationPublisher {
+    let statuses: Statuses = web::Data::new(Mutex::new(statuses.iter().map(|(id, s)| (id.to_string(), s.to_string())).collect()));
+    let server = HttpServer::new(move || App::new().app_data(statuses.clone()).service(mock_rpc))
+      .workers(1)
+      .bind(("127.0.0.1", 0))
+      .unwrap();
+    let rpc_url = format!("http://{}/", server.addrs()[0]);
+    actix_web::rt::spawn(server.run());
+    let conf = Box::leak(
+      Box::new(AttestationConf {
+        username: String::from("vsc.cv"),
+        posting_key: String::new(),
+        id: None,
+        max_attempts: None,
+      })
+    );
+    AttestationPublisher {
+      db: DbPool::init(String::from("postgres://localhost/unused")).unwrap(),
+      http_client: reqwest::Client::new(),
+      rpc_url,
+      conf,
+      key: Arc::new(PrivateKey::from_slice(&[7u8; 32]).unwrap()),
+    }
+  }
+
+  #[actix_web::test]
+  async fn test_sign() {
+    let publisher = publisher(&[]).await;
+    let (tx_id, tx) = publisher.sign("{\"statement\":{}}").await.unwrap();
+    assert_eq!(tx["expiration"], "2026-10-18T12:01:00");
+    assert_eq!(tx["operations"][0][1]["required_posting_auths"], json!(["vsc.cv"]));
+    assert_eq!(tx["operations"][0][1]["id"], "vsc_cv_attest");
+
+    let expiration = NaiveDateTime::parse_from_str("2026-10-18T12:01:00", "%Y-%m-%dT%H:%M:%S").unwrap().and_utc().timestamp() as u32;
+    let expected = Transaction::new(HEAD_BLOCK_ID, expiration, vec![CustomJson {
+      required_auths: vec![],
+      required_posting_auths: vec![String::from("vsc.cv")],
+      id: String::from("vsc_cv_attest"),
+      json: String::from("{\"statement\":{}}"),
+    }]).unwrap();
+    assert_eq!(tx["ref_block_num"], expected.ref_block_num);
+    assert_eq!(tx["ref_block_prefix"], expected.ref_block_prefix);
+    assert_eq!(tx_id, expected.id());
+    let signer = recover_pubkey(&expected.digest(&HIVE_CHAIN_ID), tx["signatures"][0].as_str().unwrap()).unwrap();
+    assert_eq!(&signer, publisher.key.verifying_key());
+  }
+
+  #[actix_web::test]
+  async fn test_previous_tx_lookup() {
+    let publisher = publisher(&[
+      ("aa", "within_mempool"),
+      ("bb", "within_reversible_block"),
+      ("cc", "within_irreversible_block"),
+      ("dd", "expired_reversible"),
+      ("ee", "too_old"),
+      ("ff", "missing"),
+    ]).await;
+    assert_eq!(publisher.is_known("aa").await, Ok(true));
+    assert_eq!(publisher.is_known("bb").await, Ok(true));
+    assert_eq!(publisher.is_known("cc").await, Ok(true));
+    assert_eq!(publisher.is_known("dd").await, Ok(false));
+    assert_eq!(publisher.is_known("ee").await, Ok(false));
+    assert_eq!(publisher.is_known("00").await, Ok(false));
+    assert!(publisher.is_known("ff").await.is_err());
+  }
+
+  #[actix_web::test]
+  async fn test_rpc_error() {
+    let publisher = publisher(&[]).await;
+    assert_eq!(
+      publisher.rpc_call("condenser_api.broadcast_transaction", json!([{}])).await,
+      Err(String::from("Could not find method condenser_api.broadcast_transaction"))
+    );
+  }
+}
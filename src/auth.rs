@@ -0,0 +1,317 @@
+use actix_web::HttpRequest;
+use serde::{ Serialize, Deserialize };
+use serde_json::{ json, Value };
+use tokio_postgres::types::Type;
+use chrono::{ NaiveDateTime, Utc, Duration };
+use sha2::{ Sha256, Digest };
+use rand::Rng;
+use jsonwebtoken::{ Header, EncodingKey, DecodingKey, Algorithm, Validation, errors::ErrorKind };
+use ring::{ rand::SystemRandom, signature::{ EcdsaKeyPair, Ed25519KeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING } };
+use base64::{ Engine as _, engine::general_purpose::URL_SAFE_NO_PAD };
+use lazy_static::lazy_static;
+use log::debug;
+use crate::{ config::{ config, JwtKeyConf }, db::DbPool, types::server::RespErr };
+
+pub struct JwtKey {
+  pub kid: String,
+  pub alg: Algorithm,
+  encoding_key: EncodingKey,
+  decoding_key: DecodingKey,
+  /// Public key in JWK format
+  pub jwk: Value,
+}
+
+impl JwtKey {
+  fn load(conf: &JwtKeyConf) -> Result<JwtKey, String> {
+    let der = hex::decode(&conf.private_key).map_err(|e| format!("Invalid hex for key {}: {}", conf.kid, e))?;
+    match conf.alg.as_str() {
+      "EdDSA" => {
+        let pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(&der).map_err(|e|
+          format!("Invalid Ed25519 key {}: {}", conf.kid, e)
+        )?;
+        let x = URL_SAFE_NO_PAD.encode(pair.public_key().as_ref());
+        Ok(JwtKey {
+          kid: conf.kid.clone(),
+          alg: Algorithm::EdDSA,
+          encoding_key: EncodingKey::from_ed_der(&der),
+          decoding_key: DecodingKey::from_ed_components(&x).map_err(|e| e.to_string())?,
+          jwk: json!({ "kty": "OKP", "crv": "Ed25519", "use": "sig", "alg": "EdDSA", "kid": &conf.kid, "x": x }),
+        })
+      }
+      "ES256" => {
+        let pair = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &der, &SystemRandom::new()).map_err(|e|
+          format!("Invalid P-256 key {}: {}", conf.kid, e)
+        )?;
+        // uncompressed point 0x04 || x || y
+        let point = pair.public_key().as_ref();
+        let x = URL_SAFE_NO_PAD.encode(&point[1..33]);
+        let y = URL_SAFE_NO_PAD.encode(&point[33..65]);
+        Ok(JwtKey {
+          kid: conf.kid.clone(),
+          alg: Algorithm::ES256,
+          encoding_key: EncodingKey::from_ec_der(&der),
+          decoding_key: DecodingKey::from_ec_components(&x, &y).map_err(|e| e.to_string())?,
+          jwk: json!({ "kty": "EC", "crv": "P-256", "use": "sig", "alg": "ES256", "kid": &conf.kid, "x": x, "y": y }),
+        })
+      }
+      _ => Err(format!("Unsupported algorithm {} for key {}, must be EdDSA or ES256", conf.alg, conf.kid)),
+    }
+  }
+}
+
+lazy_static! {
+  /// Asymmetric signing keys. The first key signs new tokens, all of them are accepted for verification.
+  pub static ref jwt_keys: Vec<JwtKey> = config.auth.keys
+    .as_ref()
+    .unwrap_or(&vec![])
+    .iter()
+    .map(JwtKey::load)
+    .collect::<Result<Vec<JwtKey>, String>>()
+    .expect("Failed to load JWT signing keys");
+}
+
+/// Generate a hex encoded PKCS#8 Ed25519 private key for the sample config.
+pub fn generate_ed25519_key() -> String {
+  hex::encode(Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap().as_ref())
+}
+
+pub fn jwks() -> Value {
+  json!({ "keys": jwt_keys.iter().map(|k| k.jwk.clone()).collect::<Vec<Value>>() })
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Claims {
+  pub user: String,
+  pub app: String,
+  pub network: String,
+  pub jti: String, // Session ID
+  pub iat: i64, // Issued at (timestamp)
+  pub exp: i64, // Expiration time (timestamp)
+}
+
+pub struct TokenPair {
+  pub access_token: String,
+  pub refresh_token: String,
+}
+
+fn hash_secret(secret: &str) -> String {
+  let mut hasher = Sha256::new();
+  hasher.update(secret);
+  hex::encode(&hasher.finalize()[..])
+}
+
+fn sign_access_token(user: &str, network: &str, jti: &str) -> Result<String, RespErr> {
+  let now = Utc::now();
+  let claims = Claims {
+    user: String::from(user),
+    app: config.auth.id.clone().unwrap(),
+    network: String::from(network),
+    jti: String::from(jti),
+    iat: now.timestamp(),
+    exp: (now + Duration::hours(1)).timestamp(),
+  };
+  match jwt_keys.first() {
+    Some(key) => {
+      let mut header = Header::new(key.alg);
+      header.kid = Some(key.kid.clone());
+      jsonwebtoken::encode(&header, &claims, &key.encoding_key).map_err(|_| RespErr::TokenGenFail)
+    }
+    None => {
+      // legacy HMAC secret
+      let decoded_secret = hex::decode(config.auth.key.clone().ok_or(RespErr::TokenGenFail)?).map_err(|_| RespErr::TokenGenFail)?;
+      jsonwebtoken
+        ::encode(&Header::default(), &claims, &EncodingKey::from_secret(&decoded_secret))
+        .map_err(|_| RespErr::TokenGenFail)
+    }
+  }
+}
+
+/// Delete a login challenge nonce issued to `account`, failing if it does not exist or has expired.
+pub async fn consume_challenge(db: &DbPool, nonce: &str, account: &str) -> Result<(), RespErr> {
+  let account = account.to_ascii_lowercase();
+  let challenge = db
+    .query(
+      "DELETE FROM vsc_cv.login_challenges WHERE nonce=$1 AND account=$2 RETURNING expiry;",
+      &[
+        (&nonce, Type::VARCHAR),
+        (&account, Type::VARCHAR),
+      ]
+    ).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  if challenge.is_empty() {
+    return Err(RespErr::ChallengeInvalid);
+  }
+  if challenge[0].get::<usize, NaiveDateTime>(0) < Utc::now().naive_utc() {
+    return Err(RespErr::ChallengeExpired);
+  }
+  Ok(())
+}
+
+/// Refresh tokens are formatted as `session_id.secret`, only the hash of the secret is stored.
+fn new_refresh_token(session_id: &str) -> (String, String) {
+  let secret = hex::encode(rand::rng().random::<[u8; 32]>());
+  (format!("{}.{}", session_id, &secret), hash_secret(&secret))
+}
+
+/// Create a new session and issue its first access and refresh token.
+pub async fn new_session(db: &DbPool, user: &str, network: &str) -> Result<TokenPair, RespErr> {
+  let session_id = hex::encode(rand::rng().random::<[u8; 16]>());
+  let (refresh_token, refresh_hash) = new_refresh_token(&session_id);
+  let now = Utc::now().naive_utc();
+  let expiry = now + Duration::seconds(config.auth.refresh_timeout.unwrap_or(2592000) as i64);
+  db
+    .query(
+      "INSERT INTO vsc_cv.sessions(id,username,network,refresh_hash,created_ts,last_refresh_ts,expiry) VALUES($1,$2,$3,$4,$5,$5,$6);",
+      &[
+        (&session_id, Type::VARCHAR),
+        (&user, Type::VARCHAR),
+        (&network, Type::VARCHAR),
+        (&refresh_hash, Type::VARCHAR),
+        (&now, Type::TIMESTAMP),
+        (&expiry, Type::TIMESTAMP),
+      ]
+    ).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  Ok(TokenPair { access_token: sign_access_token(user, network, &session_id)?, refresh_token })
+}
+
+/// Exchange a refresh token for a new token pair. The refresh token is rotated on every use,
+/// presenting a previously rotated token revokes the whole session.
+pub async fn refresh_session(db: &DbPool, refresh_token: &str) -> Result<TokenPair, RespErr> {
+  let (session_id, secret) = refresh_token.trim().split_once(".").ok_or(RespErr::TokenInvalid)?;
+  let session = db
+    .query("SELECT username, network, refresh_hash, expiry, revoked FROM vsc_cv.sessions WHERE id=$1;", &[
+      (&session_id, Type::VARCHAR),
+    ]).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  if session.is_empty() {
+    return Err(RespErr::TokenInvalid);
+  }
+  let now = Utc::now().naive_utc();
+  if session[0].get::<usize, bool>(4) {
+    return Err(RespErr::TokenRevoked);
+  }
+  if session[0].get::<usize, NaiveDateTime>(3) < now {
+    return Err(RespErr::TokenExpired);
+  }
+  if session[0].get::<usize, &str>(2) != hash_secret(secret) {
+    revoke_session(db, session_id).await?;
+    return Err(RespErr::TokenRevoked);
+  }
+  let (new_token, new_hash) = new_refresh_token(session_id);
+  // compare-and-swap on the old hash so that concurrent refreshes cannot both succeed
+  let updated = db
+    .query(
+      "UPDATE vsc_cv.sessions SET refresh_hash=$3, last_refresh_ts=$4 WHERE id=$1 AND refresh_hash=$2 RETURNING id;",
+      &[
+        (&session_id, Type::VARCHAR),
+        (&hash_secret(secret), Type::VARCHAR),
+        (&new_hash, Type::VARCHAR),
+        (&now, Type::TIMESTAMP),
+      ]
+    ).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  if updated.is_empty() {
+    revoke_session(db, session_id).await?;
+    return Err(RespErr::TokenRevoked);
+  }
+  Ok(TokenPair {
+    access_token: sign_access_token(session[0].get(0), session[0].get(1), session_id)?,
+    refresh_token: new_token,
+  })
+}
+
+pub async fn revoke_session(db: &DbPool, session_id: &str) -> Result<(), RespErr> {
+  db
+    .query("UPDATE vsc_cv.sessions SET revoked=true WHERE id=$1;", &[(&session_id, Type::VARCHAR)]).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  Ok(())
+}
+
+pub async fn verify_auth_token(req: &HttpRequest, db: &DbPool) -> Result<Claims, RespErr> {
+  if config.auth.enabled {
+    if let Some(auth_header) = req.headers().get("Authorization") {
+      let auth_value = auth_header.to_str().unwrap_or("");
+      let parts = auth_value.split(" ").collect::<Vec<&str>>();
+      debug!("Authentication header: {}", auth_value);
+      if parts.len() < 2 || parts[0] != "Bearer" {
+        return Err(RespErr::TokenMissing);
+      }
+      let header = jsonwebtoken::decode_header(parts[1]).map_err(|_| RespErr::TokenInvalid)?;
+      let legacy_key: DecodingKey;
+      let (alg, decoding_key) = match header.kid {
+        Some(kid) => {
+          let key = jwt_keys
+            .iter()
+            .find(|k| k.kid == kid)
+            .ok_or(RespErr::TokenInvalid)?;
+          (key.alg, &key.decoding_key)
+        }
+        None => {
+          let secret = hex::decode(config.auth.key.clone().ok_or(RespErr::TokenInvalid)?).map_err(|_| RespErr::TokenInvalid)?;
+          legacy_key = DecodingKey::from_secret(secret.as_slice());
+          (Algorithm::HS256, &legacy_key)
+        }
+      };
+      let mut validation = Validation::new(alg);
+      validation.validate_exp = true;
+      validation.leeway = 0;
+      let claims = (match jsonwebtoken::decode::<Claims>(parts[1], decoding_key, &validation) {
+        Ok(token_data) => {
+          // Additional manual checks if needed
+          let now = Utc::now().timestamp();
+
+          // Verify iat is in the past
+          if token_data.claims.iat > now {
+            return Err(RespErr::TokenExpired);
+          }
+
+          Ok(token_data.claims)
+        }
+        Err(err) =>
+          match err.kind() {
+            ErrorKind::ExpiredSignature => Err(RespErr::TokenExpired),
+            _ => Err(RespErr::TokenInvalid),
+          }
+      })?;
+      let session = db
+        .query("SELECT revoked FROM vsc_cv.sessions WHERE id=$1;", &[(&claims.jti, Type::VARCHAR)]).await
+        .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+      if session.is_empty() || session[0].get::<usize, bool>(0) {
+        return Err(RespErr::TokenRevoked);
+      }
+      return Ok(claims);
+    } else {
+      return Err(RespErr::TokenMissing);
+    }
+  }
+  Ok(Claims::default())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum Role {
+  Moderator,
+  Admin,
+}
+
+/// Verify the access token and require the user to be configured with at least the given role.
+pub async fn verify_role(req: &HttpRequest, db: &DbPool, min_role: Role) -> Result<Claims, RespErr> {
+  if !config.auth.enabled {
+    return Err(RespErr::Forbidden);
+  }
+  let claims = verify_auth_token(req, db).await?;
+  let role = config.auth.admins
+    .as_ref()
+    .and_then(|admins| admins.iter().find(|a| a.username == claims.user))
+    .and_then(|a| {
+      match a.role.as_str() {
+        "admin" => Some(Role::Admin),
+        "moderator" => Some(Role::Moderator),
+        _ => None,
+      }
+    });
+  match role {
+    Some(r) if r >= min_role => Ok(claims),
+    _ => Err(RespErr::Forbidden),
+  }
+}
@@ -0,0 +1,169 @@
+use tokio::sync::RwLock;
+use serde_json::{ json, Value };
+use log::debug;
+use std::{ collections::HashMap, sync::Arc, time::{ Duration, Instant } };
+use sig_verify::hive::{ pubkey_to_string, recover_pubkey };
+use crate::{ config::config, types::{ hive::{ Authority, FindAccounts, JsonRpcResp }, server::RespErr } };
+
+#[derive(Debug, PartialEq)]
+pub enum SigCheck {
+  Valid,
+  Invalid,
+  /// Authority is delegated to other accounts and cannot be resolved locally
+  Unresolved,
+}
+
+/// Posting authorities of Hive accounts cached in memory for local signature verification.
+#[derive(Clone)]
+pub struct AuthorityCache {
+  http_client: reqwest::Client,
+  entries: Arc<RwLock<HashMap<String, (Authority, Instant)>>>,
+  ttl: Duration,
+  min_refresh: Duration,
+}
+
+/// Check recovered public keys without the address prefix against a posting authority.
+fn check_authority(auth: &Authority, keys: &[String]) -> SigCheck {
+  let weight: u32 = auth.key_auths
+    .iter()
+    .filter(|(k, _)| k.len() > 3 && keys.contains(&k[3..].to_string()))
+    .map(|(_, w)| *w as u32)
+    .sum();
+  if weight >= auth.weight_threshold {
+    SigCheck::Valid
+  } else if !auth.account_auths.is_empty() {
+    SigCheck::Unresolved
+  } else {
+    SigCheck::Invalid
+  }
+}
+
+impl AuthorityCache {
+  pub fn init(http_client: reqwest::Client) -> AuthorityCache {
+    AuthorityCache {
+      http_client,
+      entries: Arc::new(RwLock::new(HashMap::new())),
+      ttl: Duration::from_secs(config.auth.authority_cache_ttl.unwrap_or(600)),
+      min_refresh: Duration::from_secs(config.auth.authority_min_refresh.unwrap_or(60)),
+    }
+  }
+
+  async fn fetch(&self, user: &str) -> Result<Authority, RespErr> {
+    debug!("Fetching posting authority of {}", user);
+    let resp = self.http_client
+      .post(config.hive_rpc.clone())
+      .json::<Value>(
+        &json!({
+          "id": 1,
+          "jsonrpc": "2.0",
+          "method": "database_api.find_accounts",
+          "params": { "accounts": [user] }
+        })
+      )
+      .send().await
+      .map_err(|_| RespErr::SigVerifyReqFail)?
+      .json::<JsonRpcResp>().await
+      .map_err(|_| RespErr::SigVerifyReqFail)?;
+    let accounts = serde_json::from_value::<FindAccounts>(resp.result.ok_or(RespErr::SigVerifyReqFail)?).map_err(|_| RespErr::SigVerifyReqFail)?;
+    let account = accounts.accounts.into_iter().find(|a| a.name == user).ok_or(RespErr::SigVerifyFail)?;
+    self.entries.write().await.insert(String::from(user), (account.posting.clone(), Instant::now()));
+    Ok(account.posting)
+  }
+
+  /// Returns the posting authority of an account and when it was fetched if it was served from cache.
+  pub async fn get(&self, user: &str) -> Result<(Authority, Option<Instant>), RespErr> {
+    if let Some((auth, fetched)) = self.entries.read().await.get(user) {
+      if fetched.elapsed() < self.ttl {
+        return Ok((auth.clone(), Some(*fetched)));
+      }
+    }
+    Ok((self.fetch(user).await?, None))
+  }
+
+  pub async fn invalidate(&self, user: &str) {
+    self.entries.write().await.remove(user);
+  }
+
+  /// Verify posting signatures over a message digest against the account's posting authority.
+  /// A cached authority that fails to verify is refreshed once in case the account keys were changed,
+  /// unless it was fetched less than `min_refresh` ago so that invalid signatures cannot force RPC calls.
+  pub async fn verify_posting(&self, user: &str, digest: &[u8; 32], signatures: &[&str]) -> Result<SigCheck, RespErr> {
+    let mut keys = Vec::new();
+    for sig in signatures {
+      let key = recover_pubkey(digest, sig).map_err(|_| RespErr::SigVerifyFail)?;
+      // compare without the address prefix
+      keys.push(pubkey_to_string(&key, ""));
+    }
+    let (mut auth, mut fetched) = self.get(user).await?;
+    loop {
+      match (check_authority(&auth, &keys), fetched) {
+        (SigCheck::Valid, _) => {
+          return Ok(SigCheck::Valid);
+        }
+        (_, Some(ts)) if ts.elapsed() >= self.min_refresh => {
+          self.invalidate(user).await;
+          auth = self.fetch(user).await?;
+          fetched = None;
+        }
+        (result, _) => {
+          return Ok(result);
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn authority(weight_threshold: u32, key_auths: &[(&str, u16)], account_auths: &[(&str, u16)]) -> Authority {
+    Authority {
+      weight_threshold,
+      account_auths: account_auths
+        .iter()
+        .map(|(a, w)| (a.to_string(), *w))
+        .collect(),
+      key_auths: key_auths
+        .iter()
+        .map(|(k, w)| (k.to_string(), *w))
+        .collect(),
+    }
+  }
+
+  #[test]
+  fn test_single_key() {
+    let auth = authority(1, &[("STM6LLegbAgLAy28EHrffBVuANFWcFgmqRMW13wBmTExqFE9SCkg4", 1)], &[]);
+    let keys = vec![String::from("6LLegbAgLAy28EHrffBVuANFWcFgmqRMW13wBmTExqFE9SCkg4")];
+    assert_eq!(check_authority(&auth, &keys), SigCheck::Valid);
+    assert_eq!(check_authority(&auth, &[String::from("8UnJiDUzMBhTMs1ie2eKpnEFYWbnz7HwydWJsPAz9xqQhJ9z6s")]), SigCheck::Invalid);
+    assert_eq!(check_authority(&auth, &[]), SigCheck::Invalid);
+  }
+
+  #[test]
+  fn test_prefix_stripped() {
+    // testnet keys use the TST prefix
+    let auth = authority(1, &[("TST6LLegbAgLAy28EHrffBVuANFWcFgmqRMW13wBmTExqFE9SCkg4", 1)], &[]);
+    assert_eq!(check_authority(&auth, &[String::from("6LLegbAgLAy28EHrffBVuANFWcFgmqRMW13wBmTExqFE9SCkg4")]), SigCheck::Valid);
+    // the prefix itself is not a key
+    assert_eq!(check_authority(&authority(1, &[("STM", 1)], &[]), &[String::new()]), SigCheck::Invalid);
+  }
+
+  #[test]
+  fn test_multisig_threshold() {
+    let auth = authority(3, &[("STMkey1", 1), ("STMkey2", 2), ("STMkey3", 1)], &[]);
+    assert_eq!(check_authority(&auth, &[String::from("key1"), String::from("key3")]), SigCheck::Invalid);
+    assert_eq!(check_authority(&auth, &[String::from("key1"), String::from("key2")]), SigCheck::Valid);
+    assert_eq!(check_authority(&auth, &[String::from("key1"), String::from("key2"), String::from("key3")]), SigCheck::Valid);
+  }
+
+  #[test]
+  fn test_account_auths_unresolved() {
+    let auth = authority(2, &[("STMkey1", 1)], &[("delegate", 1)]);
+    assert_eq!(check_authority(&auth, &[String::from("key1")]), SigCheck::Unresolved);
+    // enough key weight is valid regardless of account auths
+    let auth = authority(1, &[("STMkey1", 1)], &[("delegate", 1)]);
+    assert_eq!(check_authority(&auth, &[String::from("key1")]), SigCheck::Valid);
+  }
+}
+
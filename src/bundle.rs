@@ -0,0 +1,125 @@
+use sha2::{ Sha256, Digest };
+
+/// Hex encoded SHA-256 of a source file.
+pub fn file_hash(content: &str) -> String {
+  hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// Deterministic Merkle root over a source bundle of `(filename, hex SHA-256)` pairs.
+///
+/// Files are sorted by the bytes of their names. Each leaf is `SHA-256(0x00 || filename || 0x00 || file hash)`
+/// and each parent is `SHA-256(0x01 || left || right)`. An unpaired node at the end of a level is carried up
+/// unchanged. Returns `None` for an empty bundle.
+pub fn merkle_root(files: &[(String, String)]) -> Option<String> {
+  let mut sorted: Vec<&(String, String)> = files.iter().collect();
+  sorted.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+  let mut level: Vec<[u8; 32]> = sorted
+    .iter()
+    .map(|(name, hash)| {
+      let mut hasher = Sha256::new();
+      hasher.update([0u8]);
+      hasher.update(name.as_bytes());
+      hasher.update([0u8]);
+      hasher.update(hex::decode(hash).unwrap_or_default());
+      hasher.finalize().into()
+    })
+    .collect();
+  if level.is_empty() {
+    return None;
+  }
+  while level.len() > 1 {
+    level = level
+      .chunks(2)
+      .map(|pair| {
+        if pair.len() == 1 {
+          return pair[0];
+        }
+        let mut hasher = Sha256::new();
+        hasher.update([1u8]);
+        hasher.update(pair[0]);
+        hasher.update(pair[1]);
+        hasher.finalize().into()
+      })
+      .collect();
+  }
+  Some(hex::encode(level[0]))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn sha256(data: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for d in data {
+      hasher.update(d);
+    }
+    hasher.finalize().into()
+  }
+
+  fn leaf(name: &str, content: &str) -> [u8; 32] {
+    sha256(&[&[0u8], name.as_bytes(), &[0u8], &Sha256::digest(content.as_bytes())])
+  }
+
+  fn node(left: [u8; 32], right: [u8; 32]) -> [u8; 32] {
+    sha256(&[&[1u8], &left, &right])
+  }
+
+  fn bundle(files: &[(&str, &str)]) -> Vec<(String, String)> {
+    files
+      .iter()
+      .map(|(name, content)| (name.to_string(), file_hash(content)))
+      .collect()
+  }
+
+  #[test]
+  fn test_file_hash() {
+    assert_eq!(file_hash(""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+    assert_eq!(file_hash("abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+  }
+
+  #[test]
+  fn test_empty() {
+    assert_eq!(merkle_root(&[]), None);
+  }
+
+  #[test]
+  fn test_single_leaf() {
+    let root = merkle_root(&bundle(&[("index.ts", "export function a(): void {}")]));
+    assert_eq!(root, Some(hex::encode(leaf("index.ts", "export function a(): void {}"))));
+  }
+
+  #[test]
+  fn test_odd_leaves() {
+    let files = bundle(&[("a.ts", "a"), ("b.ts", "b"), ("c.ts", "c")]);
+    let expected = node(node(leaf("a.ts", "a"), leaf("b.ts", "b")), leaf("c.ts", "c"));
+    assert_eq!(merkle_root(&files), Some(hex::encode(expected)));
+
+    let files = bundle(&[("a.ts", "a"), ("b.ts", "b"), ("c.ts", "c"), ("d.ts", "d"), ("e.ts", "e")]);
+    let left = node(node(leaf("a.ts", "a"), leaf("b.ts", "b")), node(leaf("c.ts", "c"), leaf("d.ts", "d")));
+    assert_eq!(merkle_root(&files), Some(hex::encode(node(left, leaf("e.ts", "e")))));
+  }
+
+  #[test]
+  fn test_order_independent() {
+    let files = bundle(&[("index.ts", "1"), ("Utils.ts", "2"), ("pnpm-lock.yaml", "3"), ("a.ts", "4")]);
+    let mut reversed = files.clone();
+    reversed.reverse();
+    let mut rotated = files.clone();
+    rotated.rotate_left(1);
+    let root = merkle_root(&files);
+    assert_eq!(merkle_root(&reversed), root);
+    assert_eq!(merkle_root(&rotated), root);
+    // sorted by bytes, uppercase before lowercase
+    let expected = node(node(leaf("Utils.ts", "2"), leaf("a.ts", "4")), node(leaf("index.ts", "1"), leaf("pnpm-lock.yaml", "3")));
+    assert_eq!(root, Some(hex::encode(expected)));
+  }
+
+  #[test]
+  fn test_binds_names_and_contents() {
+    let root = merkle_root(&bundle(&[("a.ts", "a"), ("b.ts", "b")]));
+    assert_ne!(merkle_root(&bundle(&[("a.ts", "b"), ("b.ts", "a")])), root);
+    assert_ne!(merkle_root(&bundle(&[("a.ts", "a"), ("c.ts", "b")])), root);
+    assert_ne!(merkle_root(&bundle(&[("a.ts", "a")])), root);
+  }
+}
@@ -1,14 +1,18 @@
 use tokio::sync::Mutex;
 use tokio_postgres::types::Type;
 use bollard::Docker;
-use bollard::container::{ Config, CreateContainerOptions, WaitContainerOptions };
+use bollard::container::{ Config, CreateContainerOptions, LogsOptions, RemoveContainerOptions, WaitContainerOptions };
+use bollard::errors::Error as BollardErr;
 use bollard::models::{ HostConfig, ContainerWaitResponse };
 use futures_util::StreamExt;
 use serde_json;
-use chrono::Utc;
+use chrono::{ NaiveDateTime, Utc };
 use ipfs_dag::put_dag;
 use std::{ error::Error, fs, path::Path, process, sync::Arc };
 use log::{ info, debug, error };
+use crate::abi::parse_dts;
+use crate::bundle::{ file_hash, merkle_root };
+use crate::receipt::{ ReceiptStatement, RECEIPT_VERSION };
 use crate::db::DbPool;
 use crate::config::config;
 
@@ -39,6 +43,100 @@ fn delete_dir_contents(read_dir_res: Result<fs::ReadDir, std::io::Error>) {
   }
 }
 
+async fn update_attempt(db: &DbPool, addr: &str, status: i16, logs: &str) {
+  let _ = db
+    .query(
+      "SELECT vsc_cv.update_attempt($1,$2,$3,$4);",
+      &[
+        (&addr, Type::VARCHAR),
+        (&status, Type::INT2),
+        (&logs, Type::VARCHAR),
+        (&Utc::now().naive_utc(), Type::TIMESTAMP),
+      ]
+    ).await
+    .map_err(|e| { error!("Failed to update verification attempt: {}", e) });
+}
+
+/// Queue the on-chain attestation of a successful verification when enabled.
+async fn queue_attestation(db: &DbPool, addr: &str, verified_ts: &NaiveDateTime) {
+  if config.attestation.is_none() {
+    return;
+  }
+  let _ = db
+    .query(
+      "INSERT INTO vsc_cv.attestations(contract_addr,created_ts) VALUES($1,$2) ON CONFLICT(contract_addr) DO UPDATE SET status=0::SMALLINT, attempts=0, tx_id=NULL, last_error=NULL, created_ts=$2;",
+      &[
+        (&addr, Type::VARCHAR),
+        (verified_ts, Type::TIMESTAMP),
+      ]
+    ).await
+    .map_err(|e| { error!("Failed to queue attestation: {}", e) });
+}
+
+/// Sign a receipt binding the verified sources and compiler image to the contract bytecode.
+///
+/// The image is identified by its repo digest, which unlike the local image id can be pulled by anyone
+/// re-running the verification. No receipt is issued for locally built images without one.
+///
+/// `image_id` is the image of the container that compiled the contract, the configured tag may have
+/// been moved to another image since.
+async fn issue_receipt(db: &DbPool, docker: &Docker, addr: &str, bytecode_cid: &str, verified_ts: &NaiveDateTime, image_id: Option<&str>) {
+  let image_id = match image_id {
+    Some(id) => id,
+    None => {
+      error!("Compiler container image unknown, not issuing receipt for {}", addr);
+      return;
+    }
+  };
+  let image_digest = match docker.inspect_image(image_id).await {
+    Ok(image) =>
+      match image.repo_digests.and_then(|d| d.into_iter().next()) {
+        Some(d) => d,
+        None => {
+          error!("Compiler image {} has no repo digest, not issuing receipt for {}", image_id, addr);
+          return;
+        }
+      }
+    Err(e) => {
+      error!("Failed to inspect compiler image: {}", e);
+      return;
+    }
+  };
+  let files = match db.query("SELECT fname, sha256 FROM vsc_cv.source_code WHERE contract_addr=$1 AND sha256 IS NOT NULL;", &[(&addr, Type::VARCHAR)]).await {
+    Ok(f) => f,
+    Err(e) => {
+      error!("Failed to retrieve file hashes for receipt: {}", e);
+      return;
+    }
+  };
+  let files: Vec<(String, String)> = files
+    .iter()
+    .map(|f| (f.get(0), f.get(1)))
+    .collect();
+  let statement = ReceiptStatement {
+    version: RECEIPT_VERSION,
+    contract_addr: addr.to_string(),
+    bytecode_cid: bytecode_cid.to_string(),
+    source_merkle_root: merkle_root(&files),
+    compiler_image: config.ascompiler.image.clone(),
+    compiler_image_digest: image_digest,
+    verified_ts: verified_ts.format("%Y-%m-%dT%H:%M:%S%.6f").to_string(),
+  };
+  if let Some(receipt) = statement.sign() {
+    let _ = db
+      .query(
+        "INSERT INTO vsc_cv.receipts(contract_addr,statement,signature,key_id) VALUES($1,$2,$3,$4) ON CONFLICT(contract_addr) DO UPDATE SET statement=$2, signature=$3, key_id=$4;",
+        &[
+          (&addr, Type::VARCHAR),
+          (&receipt.statement, Type::VARCHAR),
+          (&receipt.signature, Type::VARCHAR),
+          (&receipt.key_id, Type::VARCHAR),
+        ]
+      ).await
+      .map_err(|e| { error!("Failed to insert verification receipt: {}", e) });
+  }
+}
+
 #[derive(Clone)]
 pub struct Compiler {
   db: DbPool,
@@ -74,9 +172,17 @@ impl Compiler {
     tokio::spawn(async move {
       let mut r = running.lock().await;
       *r = true;
+      // no build is running at this point, requeue builds interrupted by a restart
+      let _ = db
+        .query(
+          "WITH interrupted AS (UPDATE vsc_cv.contracts SET status=1::SMALLINT WHERE status=2::SMALLINT RETURNING contract_addr) SELECT vsc_cv.update_attempt(contract_addr,1::SMALLINT,NULL,NULL) FROM interrupted;",
+          &[]
+        ).await
+        .map_err(|e| { error!("Failed to requeue interrupted builds: {}", e) });
       'mainloop: loop {
+        // claim the next queued contract so that admin actions cannot change it while it is being built
         let next_contract = db.query(
-          "SELECT contract_addr, bytecode_cid, lang, dependencies FROM vsc_cv.contracts WHERE status = 1::SMALLINT ORDER BY request_ts ASC LIMIT 1",
+          "UPDATE vsc_cv.contracts SET status=2::SMALLINT WHERE contract_addr=(SELECT contract_addr FROM vsc_cv.contracts WHERE status=1::SMALLINT ORDER BY request_ts ASC LIMIT 1 FOR UPDATE SKIP LOCKED) RETURNING contract_addr, bytecode_cid, lang, dependencies;",
           &[]
         ).await;
         if next_contract.is_err() {
@@ -89,6 +195,9 @@ impl Compiler {
         }
         let next_addr: &str = next_contract[0].get(0);
         info!("Compiling contract {}", next_addr);
+        let _ = db
+          .query("SELECT vsc_cv.update_attempt($1,2::SMALLINT,NULL,NULL);", &[(&next_addr, Type::VARCHAR)]).await
+          .map_err(|e| { error!("Failed to update verification attempt: {}", e) });
         let files = db.query(
           "SELECT fname, content FROM vsc_cv.source_code WHERE contract_addr=$1;",
           &[(&next_addr, Type::VARCHAR)]
@@ -104,7 +213,7 @@ impl Compiler {
           error!("Contract returned 0 files");
           break;
         }
-        for f in files {
+        for f in files.iter() {
           let written = fs::write(
             format!("{}/src/{}", config.ascompiler.src_dir, f.get::<usize, &str>(0)),
             f.get::<usize, &str>(1)
@@ -133,8 +242,8 @@ impl Compiler {
             host_config: Some(HostConfig {
               // Volume mount
               binds: Some(vec![format!("{}:/workdir/compiler", config.ascompiler.src_dir)]),
-              // Auto-remove container on exit (equivalent to --rm)
-              auto_remove: Some(true),
+              // Container is removed after the logs are collected
+              auto_remove: Some(false),
               ..Default::default()
             }),
             ..Default::default()
@@ -148,64 +257,115 @@ impl Compiler {
           docker.start_container::<String>(&container.id, None).await.unwrap();
           // Wait for the container to finish and retrieve the exit code
           let mut stream = docker.wait_container(cont_name, Some(WaitContainerOptions { condition: "not-running" }));
-          if let Some(Ok(ContainerWaitResponse { status_code, .. })) = stream.next().await {
-            info!("Compiler exited with status code: {}", status_code);
-            if status_code == 0 {
-              let output = fs::read(format!("{}/build/build.wasm", config.ascompiler.src_dir));
-              if output.is_err() {
-                error!("build.wasm not found");
+          // non-zero exit codes are returned as errors by bollard
+          let status_code = match stream.next().await {
+            Some(Ok(ContainerWaitResponse { status_code, .. })) => status_code,
+            Some(Err(BollardErr::DockerContainerWaitError { code, .. })) => code,
+            _ => -1,
+          };
+          info!("Compiler exited with status code: {}", status_code);
+          let mut logs = String::new();
+          let mut log_stream = docker.logs(
+            cont_name,
+            Some(LogsOptions::<String> { stdout: true, stderr: true, ..Default::default() })
+          );
+          while let Some(Ok(l)) = log_stream.next().await {
+            logs.push_str(&l.to_string());
+          }
+          // the image the container ran, resolved before the container is removed
+          let image_id = match docker.inspect_container(cont_name, None).await {
+            Ok(c) => c.image,
+            Err(e) => {
+              error!("Failed to inspect compiler container: {}", e);
+              None
+            }
+          };
+          let _ = docker
+            .remove_container(cont_name, Some(RemoveContainerOptions { force: true, ..Default::default() })).await
+            .map_err(|e| { error!("Failed to remove compiler container: {}", e) });
+          if status_code == 0 {
+            let output = fs::read(format!("{}/build/build.wasm", config.ascompiler.src_dir));
+            if output.is_err() {
+              error!("build.wasm not found");
+              break;
+            }
+            let output = output.unwrap();
+            let output_cid = put_dag(output.as_slice());
+            let cid_match = output_cid == next_contract[0].get::<usize, String>(1);
+            info!("Contract bytecode match: {}", cid_match.to_string().to_ascii_uppercase());
+            if cid_match {
+              let exports: serde_json::Value = serde_json
+                ::from_str(fs::read_to_string(format!("{}/build/exports.json", config.ascompiler.src_dir)).unwrap().as_str())
+                .unwrap();
+              let abi = match fs::read_to_string(format!("{}/build/debug.d.ts", config.ascompiler.src_dir)) {
+                Ok(dts) => {
+                  let mut abi = parse_dts(&dts);
+                  let sources: Vec<&str> = files
+                    .iter()
+                    .map(|f| f.get::<usize, &str>(1))
+                    .collect();
+                  abi.detect_json_params(&sources);
+                  serde_json::to_value(abi).ok()
+                }
+                Err(e) => {
+                  error!("Failed to read debug.d.ts: {}", e);
+                  None
+                }
+              };
+              let lockfile = fs::read_to_string(format!("{}/pnpm-lock.yaml", config.ascompiler.src_dir)).unwrap();
+              let verified_ts = Utc::now().naive_utc();
+              // the lockfile is only stored together with the verified status
+              let updated_status = db.query(
+                "WITH verified AS (UPDATE vsc_cv.contracts SET status=3::SMALLINT, exports=$2::JSONB, verified_ts=$3, abi=$4 WHERE contract_addr=$1 AND status=2::SMALLINT RETURNING contract_addr) INSERT INTO vsc_cv.source_code(contract_addr, fname, is_lockfile, content, sha256) SELECT contract_addr, 'pnpm-lock.yaml', true, $5, $6 FROM verified ON CONFLICT(contract_addr, fname) DO UPDATE SET content=$5, sha256=$6 RETURNING 1;",
+                &[
+                  (&next_addr, Type::VARCHAR),
+                  (&exports, Type::JSONB),
+                  (&verified_ts, Type::TIMESTAMP),
+                  (&abi, Type::JSONB),
+                  (&lockfile, Type::VARCHAR),
+                  (&file_hash(&lockfile), Type::VARCHAR),
+                ]
+              ).await;
+              if updated_status.is_err() {
+                error!("Failed to update status after compilation: {}", updated_status.unwrap_err());
                 break;
               }
-              let output = output.unwrap();
-              let output_cid = put_dag(output.as_slice());
-              let cid_match = output_cid == next_contract[0].get::<usize, String>(1);
-              info!("Contract bytecode match: {}", cid_match.to_string().to_ascii_uppercase());
-              if cid_match {
-                let exports: serde_json::Value = serde_json
-                  ::from_str(fs::read_to_string(format!("{}/build/exports.json", config.ascompiler.src_dir)).unwrap().as_str())
-                  .unwrap();
-                let _ = db
-                  .query(
-                    "INSERT INTO vsc_cv.source_code(contract_addr, fname, is_lockfile, content) VALUES ($1,$2,true,$3);",
-                    &[
-                      (&next_addr, Type::VARCHAR),
-                      (&"pnpm-lock.yaml".to_string(), Type::VARCHAR),
-                      (&fs::read_to_string(format!("{}/pnpm-lock.yaml", config.ascompiler.src_dir)).unwrap(), Type::VARCHAR),
-                    ]
-                  ).await
-                  .map_err(|e| { error!("Failed to insert pnpm-lock.yaml: {}", e) });
-                let updated_status = db.query(
-                  "UPDATE vsc_cv.contracts SET status=3::SMALLINT, exports=$2::JSONB, verified_ts=$3 WHERE contract_addr=$1;",
-                  &[
-                    (&next_addr, Type::VARCHAR),
-                    (&exports, Type::JSONB),
-                    (&Utc::now().naive_utc(), Type::TIMESTAMP),
-                  ]
-                ).await;
-                if updated_status.is_err() {
-                  error!("Failed to update status after compilation: {}", updated_status.unwrap_err());
-                  break;
-                }
-                debug!("Exports: {}", exports);
+              if updated_status.unwrap().is_empty() {
+                info!("Verification of {} is no longer in progress, discarding the result", next_addr);
               } else {
-                let updated_status = db.query(
-                  "UPDATE vsc_cv.contracts SET status=5::SMALLINT WHERE contract_addr=$1;",
-                  &[(&next_addr, Type::VARCHAR)]
-                ).await;
-                if updated_status.is_err() {
-                  error!("Failed to update status for bytecode mismatch: {}", updated_status.unwrap_err());
-                  break;
-                }
+                update_attempt(&db, next_addr, 3, &logs).await;
+                issue_receipt(&db, &docker, next_addr, &next_contract[0].get::<usize, String>(1), &verified_ts, image_id.as_deref()).await;
+                queue_attestation(&db, next_addr, &verified_ts).await;
+                debug!("Exports: {}", exports);
               }
             } else {
               let updated_status = db.query(
-                "UPDATE vsc_cv.contracts SET status=4::SMALLINT WHERE contract_addr=$1;",
+                "UPDATE vsc_cv.contracts SET status=5::SMALLINT WHERE contract_addr=$1 AND status=2::SMALLINT RETURNING 1;",
                 &[(&next_addr, Type::VARCHAR)]
               ).await;
               if updated_status.is_err() {
-                error!("Failed to update status after failed compilation: {}", updated_status.unwrap_err());
+                error!("Failed to update status for bytecode mismatch: {}", updated_status.unwrap_err());
                 break;
               }
+              if updated_status.unwrap().is_empty() {
+                info!("Verification of {} is no longer in progress, discarding the result", next_addr);
+              } else {
+                update_attempt(&db, next_addr, 5, &logs).await;
+              }
+            }
+          } else {
+            let updated_status = db.query(
+              "UPDATE vsc_cv.contracts SET status=4::SMALLINT WHERE contract_addr=$1 AND status=2::SMALLINT RETURNING 1;",
+              &[(&next_addr, Type::VARCHAR)]
+            ).await;
+            if updated_status.is_err() {
+              error!("Failed to update status after failed compilation: {}", updated_status.unwrap_err());
+              break;
+            }
+            if updated_status.unwrap().is_empty() {
+              info!("Verification of {} is no longer in progress, discarding the result", next_addr);
+            } else {
+              update_attempt(&db, next_addr, 4, &logs).await;
             }
           }
           debug!("Deleting build artifacts");
@@ -7,6 +7,7 @@ use hex;
 use toml;
 use clap::Parser;
 use lazy_static::lazy_static;
+use crate::auth;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -30,12 +31,58 @@ pub struct ASCompilerConf {
   pub src_dir: String,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct JwtKeyConf {
+  pub kid: String,
+  /// EdDSA or ES256
+  pub alg: String,
+  /// Hex encoded PKCS#8 DER private key
+  pub private_key: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AdminConf {
+  pub username: String,
+  /// admin or moderator
+  pub role: String,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct AuthConf {
   pub enabled: bool,
   pub id: Option<String>,
   pub timeout_blocks: Option<u64>,
+  /// Validity of login challenge nonces in seconds
+  pub challenge_timeout: Option<u64>,
+  /// Maximum number of unexpired login challenges per account
+  pub max_challenges: Option<i64>,
+  /// Validity of refresh tokens in seconds
+  pub refresh_timeout: Option<u64>,
+  /// Duration in seconds to cache account posting authorities for signature verification
+  pub authority_cache_ttl: Option<u64>,
+  /// Minimum age in seconds of a cached posting authority before a failed signature check refetches it
+  pub authority_min_refresh: Option<u64>,
+  /// Legacy HS256 secret, signs new tokens only when no asymmetric keys are configured
   pub key: Option<String>,
+  pub keys: Option<Vec<JwtKeyConf>>,
+  pub admins: Option<Vec<AdminConf>>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ReceiptConf {
+  /// Hex encoded PKCS#8 DER Ed25519 private key signing verification receipts
+  pub private_key: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AttestationConf {
+  pub username: String,
+  /// WIF encoded posting key
+  pub posting_key: String,
+  /// custom_json id, defaults to vsc_cv_attest
+  pub id: Option<String>,
+  /// Broadcast attempts before an attestation is marked as failed
+  pub max_attempts: Option<u16>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -48,6 +95,8 @@ pub struct TomlConfig {
   pub auth: AuthConf,
   pub server: ServerConfig,
   pub ascompiler: ASCompilerConf,
+  pub receipts: Option<ReceiptConf>,
+  pub attestation: Option<AttestationConf>,
 }
 
 impl TomlConfig {
@@ -76,13 +125,28 @@ impl TomlConfig {
           enabled: true,
           id: Some(String::from("vsc_cv_login")),
           timeout_blocks: Some(20),
-          key: Some(hex::encode(rand::rng().random::<[u8; 32]>())),
+          challenge_timeout: Some(300),
+          max_challenges: Some(5),
+          refresh_timeout: Some(2592000),
+          authority_cache_ttl: Some(600),
+          authority_min_refresh: Some(60),
+          key: None,
+          keys: Some(
+            vec![JwtKeyConf {
+              kid: hex::encode(rand::rng().random::<[u8; 8]>()),
+              alg: String::from("EdDSA"),
+              private_key: auth::generate_ed25519_key(),
+            }]
+          ),
+          admins: None,
         },
         server: ServerConfig { address: String::from("127.0.0.1"), port: 8080 },
         ascompiler: ASCompilerConf {
           image: String::from("as-compiler"),
           src_dir: format!("{}/as_compiler", current_dir().unwrap().to_str().unwrap()),
         },
+        receipts: Some(ReceiptConf { private_key: auth::generate_ed25519_key() }),
+        attestation: None,
       };
       let serialized = toml::ser::to_string(&default_conf).unwrap();
       let _ = fs::write(&filepath, serialized);
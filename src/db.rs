@@ -6,6 +6,7 @@ use std::{ fmt, error };
 use log::info;
 
 const PSQL_CREATE_TABLES: &str = minify_sql_file!("src/sql/create_tables.sql");
+const PSQL_MIGRATE: &str = minify_sql_file!("src/sql/migrate.sql");
 const PSQL_FX: &str = minify_sql_file!("src/sql/create_functions.sql");
 
 #[derive(Debug)]
@@ -48,6 +49,7 @@ impl DbPool {
       self.execute_file(PSQL_CREATE_TABLES).await?;
     } else {
       info!("Connected to database successfully");
+      self.execute_file(PSQL_MIGRATE).await?;
     }
     self.execute_file(PSQL_FX).await?;
     Ok(())
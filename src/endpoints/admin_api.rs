@@ -0,0 +1,295 @@
+use actix_web::{ delete, get, post, web, HttpRequest, HttpResponse };
+use tokio_postgres::types::Type;
+use serde::Deserialize;
+use chrono::{ NaiveDateTime, Utc };
+use utoipa::{ IntoParams, OpenApi, ToSchema };
+use crate::{ auth::{ self, Role }, db::DbPool, types::{ cv::AuditEntry, server::{ Context, RespErr, ErrorResp, SuccessResp } } };
+
+/// Failed or mismatched verifications
+const REQUEUE_FROM: [i16; 2] = [4, 5];
+/// Pending or queued verifications, in progress ones are being built by the compiler
+const CANCEL_FROM: [i16; 2] = [0, 1];
+
+/// Change the status of a verification from one of `from` and record it in the audit log in one transaction.
+/// Returns the previous status, or None if the contract does not exist.
+async fn set_status(
+  db: &DbPool,
+  address: &str,
+  from: &[i16],
+  status: i16,
+  completed_ts: Option<NaiveDateTime>,
+  username: &str,
+  action: &str
+) -> Result<Option<i16>, RespErr> {
+  let prev = db
+    .query("SELECT vsc_cv.admin_set_status($1,$2,$3,$4,$5,$6,$7);", &[
+      (&address, Type::VARCHAR),
+      (&from, Type::INT2_ARRAY),
+      (&status, Type::INT2),
+      (&completed_ts, Type::TIMESTAMP),
+      (&username, Type::VARCHAR),
+      (&action, Type::VARCHAR),
+      (&Utc::now().naive_utc(), Type::TIMESTAMP),
+    ]).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  Ok(prev[0].get(0))
+}
+
+#[utoipa::path(
+  tag = "admin",
+  security(("bearer" = [])),
+  params(("address" = String, Path, description = "Contract address")),
+  responses(
+    (status = 200, description = "Verification requeued", body = SuccessResp),
+    (status = 400, description = "Verification is not failed or mismatched", body = ErrorResp),
+    (status = 401, description = "Unauthorized", body = ErrorResp),
+    (status = 403, description = "Insufficient permissions", body = ErrorResp),
+    (status = 404, description = "Contract not found", body = ErrorResp)
+  )
+)]
+#[post("/admin/verify/{address}/requeue")]
+async fn requeue(path: web::Path<String>, req: HttpRequest, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let claims = auth::verify_role(&req, &ctx.db, Role::Moderator).await?;
+  let address = path.into_inner();
+  match set_status(&ctx.db, &address, &REQUEUE_FROM, 1, None, &claims.user, "requeue").await? {
+    None => Ok(HttpResponse::NotFound().json(ErrorResp::new("contract not found"))),
+    Some(prev) if REQUEUE_FROM.contains(&prev) => {
+      ctx.compiler.notify();
+      Ok(HttpResponse::Ok().json(SuccessResp::ok()))
+    }
+    Some(_) => Err(RespErr::BadRequest { msg: String::from("Only failed or mismatched verifications can be requeued") }),
+  }
+}
+
+#[utoipa::path(
+  tag = "admin",
+  security(("bearer" = [])),
+  params(("address" = String, Path, description = "Contract address")),
+  responses(
+    (status = 200, description = "Verification cancelled", body = SuccessResp),
+    (status = 400, description = "Verification is not pending or queued", body = ErrorResp),
+    (status = 401, description = "Unauthorized", body = ErrorResp),
+    (status = 403, description = "Insufficient permissions", body = ErrorResp),
+    (status = 404, description = "Contract not found", body = ErrorResp)
+  )
+)]
+#[post("/admin/verify/{address}/cancel")]
+async fn cancel(path: web::Path<String>, req: HttpRequest, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let claims = auth::verify_role(&req, &ctx.db, Role::Moderator).await?;
+  let address = path.into_inner();
+  match set_status(&ctx.db, &address, &CANCEL_FROM, 6, Some(Utc::now().naive_utc()), &claims.user, "cancel").await? {
+    None => Ok(HttpResponse::NotFound().json(ErrorResp::new("contract not found"))),
+    Some(prev) if CANCEL_FROM.contains(&prev) => Ok(HttpResponse::Ok().json(SuccessResp::ok())),
+    Some(_) => Err(RespErr::BadRequest { msg: String::from("Only pending or queued verifications can be cancelled") }),
+  }
+}
+
+#[utoipa::path(
+  tag = "admin",
+  security(("bearer" = [])),
+  params(("address" = String, Path, description = "Contract address")),
+  responses(
+    (status = 200, description = "Verification deleted", body = SuccessResp),
+    (status = 400, description = "Verification is in progress", body = ErrorResp),
+    (status = 401, description = "Unauthorized", body = ErrorResp),
+    (status = 403, description = "Insufficient permissions", body = ErrorResp),
+    (status = 404, description = "Contract not found", body = ErrorResp)
+  )
+)]
+#[delete("/admin/verify/{address}")]
+async fn delete_verification(path: web::Path<String>, req: HttpRequest, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let claims = auth::verify_role(&req, &ctx.db, Role::Admin).await?;
+  let address = path.into_inner();
+  let prev = ctx.db
+    .query("SELECT vsc_cv.admin_delete_verification($1,$2,$3);", &[
+      (&address, Type::VARCHAR),
+      (&claims.user, Type::VARCHAR),
+      (&Utc::now().naive_utc(), Type::TIMESTAMP),
+    ]).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  match prev[0].get::<usize, Option<i16>>(0) {
+    None => Ok(HttpResponse::NotFound().json(ErrorResp::new("contract not found"))),
+    Some(2) => Err(RespErr::BadRequest { msg: String::from("Verification is currently in progress") }),
+    Some(_) => Ok(HttpResponse::Ok().json(SuccessResp::ok())),
+  }
+}
+
+#[derive(Deserialize, ToSchema)]
+struct ReqHide {
+  /// Whether to hide the sources of the contract
+  hidden: bool,
+}
+
+#[utoipa::path(
+  tag = "admin",
+  security(("bearer" = [])),
+  params(("address" = String, Path, description = "Contract address")),
+  request_body = ReqHide,
+  responses(
+    (status = 200, description = "Visibility of the sources updated", body = SuccessResp),
+    (status = 401, description = "Unauthorized", body = ErrorResp),
+    (status = 403, description = "Insufficient permissions", body = ErrorResp),
+    (status = 404, description = "Contract not found", body = ErrorResp)
+  )
+)]
+#[post("/admin/contract/{address}/hide")]
+async fn hide_contract(
+  path: web::Path<String>,
+  req: HttpRequest,
+  req_data: web::Json<ReqHide>,
+  ctx: web::Data<Context>
+) -> Result<HttpResponse, RespErr> {
+  let claims = auth::verify_role(&req, &ctx.db, Role::Moderator).await?;
+  let address = path.into_inner();
+  let updated = ctx.db
+    .query(
+      "WITH c AS (UPDATE vsc_cv.contracts SET hidden=$2 WHERE contract_addr=$1 RETURNING contract_addr) INSERT INTO vsc_cv.admin_audit(username,action,target,details,ts) SELECT $3,$4,contract_addr,'{}'::jsonb,$5 FROM c RETURNING 1;",
+      &[
+        (&address, Type::VARCHAR),
+        (&req_data.hidden, Type::BOOL),
+        (&claims.user, Type::VARCHAR),
+        (&(if req_data.hidden { "hide" } else { "unhide" }), Type::VARCHAR),
+        (&Utc::now().naive_utc(), Type::TIMESTAMP),
+      ]
+    ).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  if updated.is_empty() {
+    return Ok(HttpResponse::NotFound().json(ErrorResp::new("contract not found")));
+  }
+  Ok(HttpResponse::Ok().json(SuccessResp::ok()))
+}
+
+#[derive(Deserialize, ToSchema)]
+struct ReqAddName {
+  name: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+struct ReqAddLicense {
+  /// LicenseRef- identifier
+  name: String,
+  /// Full license text
+  text: Option<String>,
+}
+
+#[utoipa::path(
+  tag = "admin",
+  security(("bearer" = [])),
+  request_body = ReqAddLicense,
+  responses(
+    (status = 200, description = "License added", body = SuccessResp),
+    (status = 400, description = "Invalid or existing license name", body = ErrorResp),
+    (status = 401, description = "Unauthorized", body = ErrorResp),
+    (status = 403, description = "Insufficient permissions", body = ErrorResp)
+  )
+)]
+#[post("/admin/licenses")]
+async fn add_license(req: HttpRequest, req_data: web::Json<ReqAddLicense>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let claims = auth::verify_role(&req, &ctx.db, Role::Admin).await?;
+  // SPDX licenses are already bundled, only custom license references can be added
+  let id = req_data.name.strip_prefix("LicenseRef-").unwrap_or("");
+  if id.is_empty() || req_data.name.len() > 64 || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-') {
+    return Err(RespErr::BadRequest {
+      msg: String::from("License name must be a LicenseRef- identifier of up to 64 characters containing only A-Z, a-z, 0-9, . and -"),
+    });
+  }
+  let inserted = ctx.db
+    .query(
+      "WITH l AS (INSERT INTO vsc_cv.licenses(id,name,text) SELECT COALESCE(MAX(id)+1,0),$1,$2 FROM vsc_cv.licenses ON CONFLICT(name) DO NOTHING RETURNING id, name) INSERT INTO vsc_cv.admin_audit(username,action,target,details,ts) SELECT $3,'add_license',name,jsonb_build_object('id',id),$4 FROM l RETURNING 1;",
+      &[
+        (&req_data.name, Type::VARCHAR),
+        (&req_data.text, Type::VARCHAR),
+        (&claims.user, Type::VARCHAR),
+        (&Utc::now().naive_utc(), Type::TIMESTAMP),
+      ]
+    ).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  if inserted.is_empty() {
+    return Err(RespErr::BadRequest { msg: String::from("License already exists") });
+  }
+  Ok(HttpResponse::Ok().json(SuccessResp::ok()))
+}
+
+#[utoipa::path(
+  tag = "admin",
+  security(("bearer" = [])),
+  request_body = ReqAddName,
+  responses(
+    (status = 200, description = "Language added", body = SuccessResp),
+    (status = 400, description = "Invalid or existing language name", body = ErrorResp),
+    (status = 401, description = "Unauthorized", body = ErrorResp),
+    (status = 403, description = "Insufficient permissions", body = ErrorResp)
+  )
+)]
+#[post("/admin/languages")]
+async fn add_language(req: HttpRequest, req_data: web::Json<ReqAddName>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let claims = auth::verify_role(&req, &ctx.db, Role::Admin).await?;
+  if req_data.name.is_empty() || req_data.name.len() > 20 {
+    return Err(RespErr::BadRequest { msg: String::from("Language name must be between 1 and 20 characters") });
+  }
+  let inserted = ctx.db
+    .query(
+      "WITH l AS (INSERT INTO vsc_cv.languages(id,name) SELECT COALESCE(MAX(id)+1,0),$1 FROM vsc_cv.languages ON CONFLICT(name) DO NOTHING RETURNING id, name) INSERT INTO vsc_cv.admin_audit(username,action,target,details,ts) SELECT $2,'add_language',name,jsonb_build_object('id',id),$3 FROM l RETURNING 1;",
+      &[
+        (&req_data.name, Type::VARCHAR),
+        (&claims.user, Type::VARCHAR),
+        (&Utc::now().naive_utc(), Type::TIMESTAMP),
+      ]
+    ).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  if inserted.is_empty() {
+    return Err(RespErr::BadRequest { msg: String::from("Language already exists") });
+  }
+  Ok(HttpResponse::Ok().json(SuccessResp::ok()))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ListAuditOpts {
+  /// Only return entries with an id up to this one
+  last_id: Option<i32>,
+  /// Number of entries, at most 100
+  count: Option<i64>,
+}
+
+#[utoipa::path(
+  tag = "admin",
+  security(("bearer" = [])),
+  params(ListAuditOpts),
+  responses(
+    (status = 200, description = "Audit log entries, latest first", body = Vec<AuditEntry>),
+    (status = 401, description = "Unauthorized", body = ErrorResp),
+    (status = 403, description = "Insufficient permissions", body = ErrorResp)
+  )
+)]
+#[get("/admin/audit")]
+async fn list_audit(req: HttpRequest, params: web::Query<ListAuditOpts>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  auth::verify_role(&req, &ctx.db, Role::Moderator).await?;
+  let count = params.count.unwrap_or(100).clamp(1, 100);
+  let last_id = params.last_id.unwrap_or(i32::MAX);
+  let rows = ctx.db
+    .query(
+      "SELECT id, username, action, target, details, ts FROM vsc_cv.admin_audit WHERE id <= $1 ORDER BY id DESC LIMIT $2;",
+      &[
+        (&last_id, Type::INT4),
+        (&count, Type::INT8),
+      ]
+    ).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  let result: Vec<AuditEntry> = rows
+    .iter()
+    .map(|a| AuditEntry {
+      id: a.get(0),
+      username: a.get(1),
+      action: a.get(2),
+      target: a.get(3),
+      details: a.get(4),
+      ts: a.get::<usize, NaiveDateTime>(5).format("%Y-%m-%dT%H:%M:%S%.6f").to_string(),
+    })
+    .collect();
+  Ok(HttpResponse::Ok().json(result))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(requeue, cancel, delete_verification, hide_contract, add_license, add_language, list_audit))]
+pub struct AdminApiDoc;
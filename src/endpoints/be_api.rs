@@ -1,19 +1,52 @@
-use actix_web::{ get, web, HttpResponse, Responder };
+use actix_web::{ get, post, web, HttpResponse, Responder };
+use async_graphql::http::{ playground_source, GraphQLPlaygroundConfig };
 use futures_util::StreamExt;
-use mongodb::{ bson::{ doc, Bson }, options::{ FindOneOptions, FindOptions } };
-use serde::Deserialize;
-use serde_json::{ json, Value };
-use std::cmp::{ min, max };
+use mongodb::{ bson::{ doc, Bson, Document }, options::{ FindOneOptions, FindOptions }, Collection };
+use serde::{ de::DeserializeOwned, Deserialize };
+use serde_json::Value;
+use std::{ cmp::{ min, max }, collections::HashMap };
+use utoipa::{ IntoParams, OpenApi };
 use crate::{
   config::config,
-  types::{ hive::{ CustomJson, TxByHash }, server::{ Context, RespErr }, vsc::{ LedgerBalance, RcUsedAtHeight, WitnessStat } },
+  endpoints::cv_api,
+  types::{
+    be::{
+      AccountHistory,
+      AccountHistoryEntry,
+      AccountRcUsed,
+      BalancePoint,
+      ContractActivity,
+      ContractCaller,
+      ContractDetail,
+      ContractStats,
+      EpochDetail,
+      EpochDiff,
+      EpochParticipation,
+      L2TxOutput,
+      Props,
+      ProtocolVersionChange,
+      QuorumEntry,
+      SearchResult,
+      SearchValue,
+      TxAnchor,
+      TxList,
+      TxOutput,
+      WeightChange,
+      WitnessStatsDetail,
+    },
+    hive::{ CustomJson, TxByHash },
+    server::{ Context, RespErr, ErrorResp },
+    vsc::{ BlockHeaderRecord, Contract, ElectionResultRecord, LedgerBalance, RcUsedAtHeight, TransactionRecord, WitnessStat, Witnesses },
+  },
 };
 
+#[utoipa::path(tag = "be-api", responses((status = 200, description = "Hello world", body = String, content_type = "text/plain")))]
 #[get("")]
 async fn hello() -> impl Responder {
   HttpResponse::Ok().body("Hello world!")
 }
 
+#[utoipa::path(tag = "be-api", responses((status = 200, description = "Network statistics", body = Props)))]
 #[get("/props")]
 async fn props(ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let pipeline = vec![doc! {
@@ -40,19 +73,21 @@ async fn props(ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   };
   let tx_count = ctx.vsc_db.tx_pool.estimated_document_count().await.map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
   Ok(
-    HttpResponse::Ok().json(
-      json!({
-        "last_processed_block": last_l1_block,
-        "l2_block_height": block_count,
-        "witnesses": witness_count,
-        "epoch": epoch.saturating_sub(1),
-        "contracts": contracts,
-        "transactions": tx_count
-      })
-    )
+    HttpResponse::Ok().json(Props {
+      last_processed_block: last_l1_block,
+      l2_block_height: block_count,
+      witnesses: witness_count,
+      epoch: epoch.saturating_sub(1),
+      contracts,
+      transactions: tx_count,
+    })
   )
 }
 
+#[utoipa::path(
+  tag = "be-api",
+  responses((status = 200, description = "Latest registration of every witness, the stored documents are returned as is without `_id`", body = Vec<Witnesses>))
+)]
 #[get("/witnesses")]
 async fn list_witnesses(ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let pipeline = vec![
@@ -80,6 +115,11 @@ async fn list_witnesses(ctx: web::Data<Context>) -> Result<HttpResponse, RespErr
   Ok(HttpResponse::Ok().json(results))
 }
 
+#[utoipa::path(
+  tag = "be-api",
+  params(("username" = String, Path, description = "Hive username")),
+  responses((status = 200, description = "Latest registration of the witness", body = Witnesses), (status = 404, description = "Witness does not exist", body = ErrorResp))
+)]
 #[get("/witness/{username}")]
 async fn get_witness(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let user = path.into_inner();
@@ -93,12 +133,24 @@ async fn get_witness(path: web::Path<String>, ctx: web::Data<Context>) -> Result
       .map_err(|e| RespErr::DbErr { msg: e.to_string() })?
   {
     Some(wit) => Ok(HttpResponse::Ok().json(wit)),
-    None => Ok(HttpResponse::NotFound().json(json!({"error": "witness does not exist"}))),
+    None => Ok(HttpResponse::NotFound().json(ErrorResp::new("witness does not exist"))),
   }
 }
 
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct WitnessStatsOpts {
+  /// Number of latest epochs to compute the uptime over, at most 100. Defaults to 10.
+  epochs: Option<i64>,
+}
+
+#[utoipa::path(
+  tag = "be-api",
+  params(("username" = String, Path, description = "Hive username"), WitnessStatsOpts),
+  responses((status = 200, description = "Block production, election and block signing statistics of the witness", body = WitnessStatsDetail))
+)]
 #[get("/witness/{username}/stats")]
-async fn get_witness_stats(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+async fn get_witness_stats(path: web::Path<String>, params: web::Query<WitnessStatsOpts>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let user = path.into_inner();
   let stats = ctx.vsc_db.witness_stats
     .find_one(doc! { "_id": &user }).await
@@ -109,18 +161,58 @@ async fn get_witness_stats(path: web::Path<String>, ctx: web::Data<Context>) ->
       election_count: None,
       last_block: None,
       last_epoch: None,
+      eligible_blocks: None,
+      signed_blocks: None,
     });
-  Ok(HttpResponse::Ok().json(stats))
+  let mut cursor = ctx.vsc_db.witness_participation
+    .find(doc! { "account": &user })
+    .sort(doc! { "epoch": -1 })
+    .limit(params.epochs.unwrap_or(10).clamp(1, 100)).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  let mut epochs = Vec::new();
+  while let Some(doc) = cursor.next().await {
+    let p = doc.map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+    epochs.push(EpochParticipation {
+      epoch: p.epoch,
+      eligible: p.eligible,
+      signed: p.signed,
+      rate: if p.eligible > 0 { (p.signed as f64) / (p.eligible as f64) } else { 0.0 },
+    });
+  }
+  let participation_rate = match (stats.eligible_blocks, stats.signed_blocks) {
+    (Some(eligible), signed) if eligible > 0 => Some((signed.unwrap_or(0) as f64) / (eligible as f64)),
+    _ => None,
+  };
+  let window_eligible: i32 = epochs.iter().map(|e| e.eligible).sum();
+  let window_signed: i32 = epochs.iter().map(|e| e.signed).sum();
+  let uptime = if window_eligible > 0 { Some((window_signed as f64) / (window_eligible as f64)) } else { None };
+  Ok(HttpResponse::Ok().json(WitnessStatsDetail { stats, participation_rate, uptime, epochs }))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct BalanceOpts {
+  /// Block height to look up the balance at, hive_unstaking is unset for historical balances
+  height: Option<i64>,
 }
 
+#[utoipa::path(
+  tag = "be-api",
+  params(("username" = String, Path, description = "Account prefixed by hive: or did:"), BalanceOpts),
+  responses((status = 200, description = "Ledger balance of the account", body = LedgerBalance))
+)]
 #[get("/balance/{username}")]
-async fn get_balance(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+async fn get_balance(path: web::Path<String>, params: web::Query<BalanceOpts>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let user = path.into_inner(); // must be prefixed by hive: or did: (!)
   let opt = FindOneOptions::builder()
     .sort(doc! { "block_height": -1 })
     .build();
+  let mut filter = doc! { "account": user.clone() };
+  if let Some(height) = params.height {
+    filter.insert("block_height", doc! { "$lte": height });
+  }
   let mut bal = ctx.vsc_db.balances
-    .find_one(doc! { "account": user.clone() })
+    .find_one(filter.clone())
     .with_options(opt.clone()).await
     .map_err(|e| RespErr::DbErr { msg: e.to_string() })?
     .unwrap_or(LedgerBalance {
@@ -137,7 +229,7 @@ async fn get_balance(path: web::Path<String>, ctx: web::Data<Context>) -> Result
     });
   bal.rc_used = Some(
     ctx.vsc_db.rc
-      .find_one(doc! { "account": user.clone() })
+      .find_one(filter)
       .with_options(opt.clone()).await
       .map_err(|e| RespErr::DbErr { msg: e.to_string() })?
       .unwrap_or(RcUsedAtHeight {
@@ -145,6 +237,10 @@ async fn get_balance(path: web::Path<String>, ctx: web::Data<Context>) -> Result
         amount: 0,
       })
   );
+  if params.height.is_some() {
+    // unstakes are only tracked by their current status
+    return Ok(HttpResponse::Ok().json(bal));
+  }
   let unstaking_pipeline = vec![
     doc! {
       "$match": doc! {
@@ -174,13 +270,300 @@ async fn get_balance(path: web::Path<String>, ctx: web::Data<Context>) -> Result
   Ok(HttpResponse::Ok().json(bal))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Deserialize)]
+struct SampleBucket<T> {
+  #[serde(rename = "_id")]
+  sample: i64,
+  doc: T,
+}
+
+/// Latest record of the account at or before every sample height `from + k * step`, carried forward to samples without a record of their own.
+async fn sample_records<T: DeserializeOwned + Send + Sync + Clone>(
+  collection: &Collection<T>,
+  account: &str,
+  from: i64,
+  step: f64,
+  points: usize
+) -> Result<Vec<Option<T>>, RespErr> {
+  let to = from + (((points - 1) as f64) * step).floor() as i64;
+  let initial = collection
+    .find_one(doc! { "account": account, "block_height": { "$lte": from } })
+    .sort(doc! { "block_height": -1 }).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  let pipeline = vec![
+    doc! { "$match": { "account": account, "block_height": { "$gt": from, "$lte": to } } },
+    doc! { "$sort": { "block_height": 1 } },
+    doc! {
+      "$group": {
+        "_id": { "$toLong": { "$ceil": { "$divide": [{ "$subtract": ["$block_height", from] }, step] } } },
+        "doc": { "$last": "$$ROOT" }
+      }
+    }
+  ];
+  let mut cursor = collection
+    .aggregate(pipeline)
+    .with_type::<SampleBucket<T>>().await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  let mut samples: Vec<Option<T>> = vec![None; points];
+  samples[0] = initial;
+  while let Some(bucket) = cursor.next().await {
+    let bucket = bucket.map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+    if let Some(sample) = samples.get_mut(bucket.sample as usize) {
+      *sample = Some(bucket.doc);
+    }
+  }
+  for k in 1..points {
+    if samples[k].is_none() {
+      samples[k] = samples[k - 1].clone();
+    }
+  }
+  Ok(samples)
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct BalanceHistoryOpts {
+  /// Defaults to the height of the first balance record of the account
+  from_block: Option<i64>,
+  /// Defaults to the last processed Hive block
+  to_block: Option<i64>,
+  /// Number of samples evenly spaced between from_block and to_block, at most 500
+  points: Option<i64>,
+}
+
+#[utoipa::path(
+  tag = "be-api",
+  params(("username" = String, Path, description = "Account prefixed by hive: or did:"), BalanceHistoryOpts),
+  responses(
+    (status = 200, description = "Balances of the account at evenly spaced block heights, oldest first", body = Vec<BalancePoint>),
+    (status = 400, description = "from_block is after to_block", body = ErrorResp)
+  )
+)]
+#[get("/balance/{username}/history")]
+async fn get_balance_history(path: web::Path<String>, params: web::Query<BalanceHistoryOpts>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let user = path.into_inner();
+  let from = match params.from_block {
+    Some(from_block) => from_block,
+    None =>
+      ctx.vsc_db.balances
+        .find_one(doc! { "account": &user })
+        .sort(doc! { "block_height": 1 }).await
+        .map_err(|e| RespErr::DbErr { msg: e.to_string() })?
+        .map(|b| b.block_height as i64)
+        .unwrap_or(0),
+  };
+  let to = match params.to_block {
+    Some(to_block) => to_block,
+    None =>
+      ctx.vsc_db.l1_blocks
+        .find_one(doc! { "type": "metadata" }).await
+        .map_err(|e| RespErr::DbErr { msg: e.to_string() })?
+        .map(|state| state.head_height)
+        .unwrap_or(0),
+  };
+  if from > to {
+    return Err(RespErr::BadRequest { msg: String::from("from_block must not be after to_block") });
+  }
+  let points = params.points.unwrap_or(100).clamp(1, 500).min(to - from + 1) as usize;
+  let step = if points > 1 { ((to - from) as f64) / ((points - 1) as f64) } else { 1.0 };
+  let balances = sample_records(&ctx.vsc_db.balances, &user, from, step, points).await?;
+  let rcs = sample_records(&ctx.vsc_db.rc, &user, from, step, points).await?;
+  let results: Vec<BalancePoint> = balances
+    .into_iter()
+    .zip(rcs)
+    .enumerate()
+    .map(|(k, (bal, rc))| BalancePoint {
+      block_height: (from + ((k as f64) * step).floor() as i64) as u64,
+      hive: bal.as_ref().map(|b| b.hive).unwrap_or(0),
+      hbd: bal.as_ref().map(|b| b.hbd).unwrap_or(0),
+      hbd_savings: bal.as_ref().map(|b| b.hbd_savings).unwrap_or(0),
+      hive_consensus: bal.as_ref().map(|b| b.hive_consensus).unwrap_or(0),
+      rc_used: rc.map(|r| r.amount).unwrap_or(0),
+    })
+    .collect();
+  Ok(HttpResponse::Ok().json(results))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct AccountHistoryOpts {
+  /// next_cursor of the previous page
+  cursor: Option<String>,
+  /// Number of entries, at most 100
+  count: Option<i64>,
+  asset: Option<String>,
+  /// Op type, i.e. transfer, withdraw, deposit or call
+  #[serde(rename = "type")]
+  #[param(rename = "type")]
+  op_type: Option<String>,
+  from_block: Option<i64>,
+  to_block: Option<i64>,
+}
+
+/// Entries are ordered by height descending, then by kind, id descending and index. The cursor is the
+/// position of the last entry of a page encoded as `height.kind.idx.id`. Returns its height along with
+/// the filter on the entries after it.
+fn history_cursor_filter(cursor: &str) -> Result<(i64, Document), RespErr> {
+  let invalid = || RespErr::BadRequest { msg: String::from("Invalid cursor") };
+  let parts: Vec<&str> = cursor.splitn(4, '.').collect();
+  if parts.len() != 4 {
+    return Err(invalid());
+  }
+  let height = parts[0].parse::<i64>().map_err(|_| invalid())?;
+  let idx = parts[2].parse::<i64>().map_err(|_| invalid())?;
+  let (kind, id) = (parts[1], parts[3]);
+  Ok((
+    height,
+    doc! {
+      "$or": [
+        { "height": { "$lt": height } },
+        { "height": height, "kind": { "$gt": kind } },
+        { "height": height, "kind": kind, "id": { "$lt": id } },
+        { "height": height, "kind": kind, "id": id, "idx": { "$gt": idx } }
+      ]
+    },
+  ))
+}
+
+#[utoipa::path(
+  tag = "be-api",
+  params(("account" = String, Path, description = "Account prefixed by hive: or did:, hive: is assumed when omitted"), AccountHistoryOpts),
+  responses((status = 200, description = "Ledger actions, ledger ops and contract calls of the account, latest first", body = AccountHistory), (status = 400, description = "Invalid cursor", body = ErrorResp))
+)]
+#[get("/account/{account}/history")]
+async fn get_account_history(
+  path: web::Path<String>,
+  params: web::Query<AccountHistoryOpts>,
+  ctx: web::Data<Context>
+) -> Result<HttpResponse, RespErr> {
+  let mut account = path.into_inner();
+  if !account.starts_with("hive:") && !account.starts_with("did:") {
+    account = format!("hive:{}", account);
+  }
+  let count = params.count.unwrap_or(100).clamp(1, 100);
+  let cursor = params.cursor.as_deref().map(history_cursor_filter).transpose()?;
+  // the block range and cursor height are matched on the stored height field of each branch of the union,
+  // which is then sorted and limited to one page on its own
+  let mut height_range = doc! {};
+  if let Some(from_block) = params.from_block {
+    height_range.insert("$gte", from_block);
+  }
+  let max_height = match (params.to_block, cursor.as_ref().map(|(h, _)| *h)) {
+    (Some(to_block), Some(h)) => Some(to_block.min(h)),
+    (to_block, h) => to_block.or(h),
+  };
+  if let Some(max_height) = max_height {
+    height_range.insert("$lte", max_height);
+  }
+  let in_range = |mut filter: Document, height_field: &str| {
+    if !height_range.is_empty() {
+      filter.insert(height_field, height_range.clone());
+    }
+    filter
+  };
+  let mut filter = doc! {};
+  if let Some(asset) = &params.asset {
+    filter.insert("asset", asset);
+  }
+  if let Some(op_type) = &params.op_type {
+    filter.insert("type", op_type);
+  }
+  if let Some((_, after)) = cursor {
+    filter.extend(after);
+  }
+  let page = [
+    doc! { "$match": filter },
+    doc! { "$sort": { "height": -1, "kind": 1, "id": -1, "idx": 1 } },
+    doc! { "$limit": count },
+  ];
+  let involved = in_range(doc! { "$or": [{ "ledger.to": &account }, { "ledger.from": &account }] }, "ledger.blockheight");
+  let mut ledger_ops = vec![
+    doc! { "$match": involved.clone() },
+    doc! { "$unwind": { "path": "$ledger", "includeArrayIndex": "idx" } },
+    doc! { "$match": involved },
+    doc! {
+      "$project": {
+        "_id": 0,
+        "kind": { "$literal": "ledger_op" },
+        "id": "$id",
+        "idx": "$idx",
+        "height": "$ledger.blockheight",
+        "type": "$ledger.type",
+        "asset": "$ledger.asset",
+        "amount": "$ledger.amount",
+        "from": "$ledger.from",
+        "to": "$ledger.to",
+        "memo": "$ledger.memo",
+        "status": "$status"
+      }
+    }
+  ];
+  ledger_ops.extend(page.clone());
+  let mut contract_calls = vec![
+    doc! {
+      "$match": in_range(doc! { "required_auths": &account, "data.type": "call", "anchr_height": { "$exists": true } }, "anchr_height"),
+    },
+    doc! {
+      "$project": {
+        "_id": 0,
+        "kind": { "$literal": "contract_call" },
+        "id": "$id",
+        "idx": { "$literal": 0 },
+        "height": "$anchr_height",
+        "type": "$data.type",
+        "status": "$status",
+        "contract_id": "$data.contract_id",
+        "action": "$data.action"
+      }
+    }
+  ];
+  contract_calls.extend(page.clone());
+  let mut pipeline = vec![
+    doc! { "$match": in_range(doc! { "to": &account }, "block_height") },
+    doc! {
+      "$project": {
+        "_id": 0,
+        "kind": { "$literal": "ledger_action" },
+        "id": "$id",
+        "idx": { "$literal": 0 },
+        "height": "$block_height",
+        "type": "$type",
+        "asset": "$asset",
+        "amount": "$amount",
+        "to": "$to",
+        "memo": "$memo",
+        "status": "$status"
+      }
+    }
+  ];
+  pipeline.extend(page.clone());
+  pipeline.push(doc! { "$unionWith": { "coll": ctx.vsc_db.tx_pool.name(), "pipeline": ledger_ops } });
+  pipeline.push(doc! { "$unionWith": { "coll": ctx.vsc_db.tx_pool.name(), "pipeline": contract_calls } });
+  pipeline.extend(page);
+  let mut cursor = ctx.vsc_db.ledger_actions
+    .aggregate(pipeline)
+    .with_type::<AccountHistoryEntry>().await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  let mut entries = Vec::new();
+  while let Some(entry) = cursor.next().await {
+    entries.push(entry.map_err(|e| RespErr::DbErr { msg: e.to_string() })?);
+  }
+  let next_cursor = match entries.last() {
+    Some(last) if (entries.len() as i64) == count => Some(format!("{}.{}.{}.{}", last.height, last.kind, last.idx, last.id)),
+    _ => None,
+  };
+  Ok(HttpResponse::Ok().json(AccountHistory { entries, next_cursor }))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct ListEpochOpts {
   last_epoch: Option<i64>,
   count: Option<i64>,
   proposer: Option<String>,
 }
 
+#[utoipa::path(tag = "be-api", params(ListEpochOpts), responses((status = 200, description = "Elections, latest first", body = Vec<ElectionResultRecord>)))]
 #[get("/epochs")]
 async fn list_epochs(params: web::Query<ListEpochOpts>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let last_epoch = params.last_epoch;
@@ -208,22 +591,110 @@ async fn list_epochs(params: web::Query<ListEpochOpts>, ctx: web::Data<Context>)
   Ok(HttpResponse::Ok().json(results))
 }
 
-#[get("/epoch/{epoch}")]
-async fn get_epoch(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+/// Changes of election `cur` from the election `prev` before it.
+fn election_diff(prev: Option<&ElectionResultRecord>, cur: &ElectionResultRecord) -> EpochDiff {
+  let member_weights = |e: &ElectionResultRecord| -> Vec<(String, u64)> {
+    e.members
+      .iter()
+      .enumerate()
+      .map(|(i, m)| (m.account.clone(), e.weights.get(i).copied().unwrap_or(0)))
+      .collect()
+  };
+  let prev_weights = prev.map(member_weights).unwrap_or_default();
+  let cur_weights = member_weights(cur);
+  let prev_map: HashMap<&str, u64> = prev_weights.iter().map(|(a, w)| (a.as_str(), *w)).collect();
+  let cur_map: HashMap<&str, u64> = cur_weights.iter().map(|(a, w)| (a.as_str(), *w)).collect();
+  let mut joined = Vec::new();
+  let mut weight_changes = Vec::new();
+  for (account, weight) in &cur_weights {
+    match prev_map.get(account.as_str()) {
+      None => joined.push(account.clone()),
+      Some(previous) if previous != weight =>
+        weight_changes.push(WeightChange {
+          account: account.clone(),
+          previous: *previous,
+          current: *weight,
+          change: (*weight as i64) - (*previous as i64),
+        }),
+      Some(_) => (),
+    }
+  }
+  let left = prev_weights
+    .iter()
+    .filter(|(account, _)| !cur_map.contains_key(account.as_str()))
+    .map(|(account, _)| account.clone())
+    .collect();
+  EpochDiff {
+    previous_epoch: prev.map(|p| p.epoch),
+    joined,
+    left,
+    weight_changes,
+    total_weight_change: (cur.total_weight as i64) - (prev.map(|p| p.total_weight).unwrap_or(0) as i64),
+    protocol_version: match prev {
+      Some(p) if p.protocol_version != cur.protocol_version =>
+        Some(ProtocolVersionChange { previous: p.protocol_version, current: cur.protocol_version }),
+      _ => None,
+    },
+  }
+}
+
+async fn epoch_with_diff(path: web::Path<String>, ctx: &Context) -> Result<Option<EpochDetail>, RespErr> {
   let epoch_num = path
     .into_inner()
-    .parse::<i32>()
+    .parse::<i64>()
     .map_err(|_| RespErr::BadRequest { msg: String::from("Invalid epoch number") })?;
   let epoch = ctx.vsc_db.elections
     .find_one(doc! { "epoch": epoch_num }).await
     .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
   match epoch {
+    Some(ep) => {
+      let prev = ctx.vsc_db.elections
+        .find_one(doc! { "epoch": { "$lt": epoch_num } })
+        .sort(doc! { "epoch": -1 }).await
+        .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+      let diff = election_diff(prev.as_ref(), &ep);
+      Ok(Some(EpochDetail { election: ep, diff }))
+    }
+    None => Ok(None),
+  }
+}
+
+#[utoipa::path(
+  tag = "be-api",
+  params(("epoch" = i32, Path, description = "Epoch number")),
+  responses(
+    (status = 200, description = "Election result of the epoch and its changes from the previous election", body = EpochDetail),
+    (status = 400, description = "Invalid epoch number", body = ErrorResp),
+    (status = 404, description = "Epoch does not exist", body = ErrorResp)
+  )
+)]
+#[get("/epoch/{epoch}")]
+async fn get_epoch(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  match epoch_with_diff(path, &ctx).await? {
     Some(ep) => Ok(HttpResponse::Ok().json(ep)),
-    None => Ok(HttpResponse::NotFound().json(json!({"error": "Epoch does not exist"}))),
+    None => Ok(HttpResponse::NotFound().json(ErrorResp::new("Epoch does not exist"))),
+  }
+}
+
+#[utoipa::path(
+  tag = "be-api",
+  params(("epoch" = i32, Path, description = "Epoch number")),
+  responses(
+    (status = 200, description = "Members who joined or left, weight changes, total weight and protocol version change from the previous election", body = EpochDiff),
+    (status = 400, description = "Invalid epoch number", body = ErrorResp),
+    (status = 404, description = "Epoch does not exist", body = ErrorResp)
+  )
+)]
+#[get("/epoch/{epoch}/diff")]
+async fn get_epoch_diff(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  match epoch_with_diff(path, &ctx).await? {
+    Some(ep) => Ok(HttpResponse::Ok().json(ep.diff)),
+    None => Ok(HttpResponse::NotFound().json(ErrorResp::new("Epoch does not exist"))),
   }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct ListBlockOpts {
   last_block_id: Option<i64>,
   count: Option<i64>,
@@ -231,6 +702,7 @@ struct ListBlockOpts {
   epoch: Option<i64>,
 }
 
+#[utoipa::path(tag = "be-api", params(ListBlockOpts), responses((status = 200, description = "Indexed blocks, latest first", body = Vec<BlockHeaderRecord>)))]
 #[get("/blocks")]
 async fn list_blocks(params: web::Query<ListBlockOpts>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let last_block_id = params.last_block_id;
@@ -262,6 +734,16 @@ async fn list_blocks(params: web::Query<ListBlockOpts>, ctx: web::Data<Context>)
   Ok(HttpResponse::Ok().json(results))
 }
 
+#[utoipa::path(
+  tag = "be-api",
+  path = "/block/by-{by}/{id}",
+  params(("by" = String, Path, description = "id, cid or slot"), ("id" = String, Path, description = "Block number, CID or slot height")),
+  responses(
+    (status = 200, description = "Block header", body = BlockHeaderRecord),
+    (status = 400, description = "Invalid by clause or id", body = ErrorResp),
+    (status = 404, description = "Block not found", body = ErrorResp)
+  )
+)]
 #[get("/block/by-{by}/{id}")]
 async fn get_block(path: web::Path<(String, String)>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let (by, id) = path.into_inner();
@@ -278,10 +760,84 @@ async fn get_block(path: web::Path<(String, String)>, ctx: web::Data<Context>) -
   let epoch = ctx.vsc_db.blocks.find_one(filter).await.map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
   match epoch {
     Some(block) => { Ok(HttpResponse::Ok().json(block)) }
-    None => Ok(HttpResponse::NotFound().json(json!({"error": "Block not found"}))),
+    None => Ok(HttpResponse::NotFound().json(ErrorResp::new("Block not found"))),
+  }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct QuorumOpts {
+  /// Include entries whose quorum margin is below this fraction of the eligible weight. Defaults to 0.05, 0 lists under-signed entries only.
+  borderline: Option<f64>,
+  /// Latest L2 block number or epoch to include
+  last_id: Option<i64>,
+  /// Number of entries, at most 100
+  count: Option<i64>,
+}
+
+#[utoipa::path(
+  tag = "be-api",
+  params(("kind" = String, Path, description = "blocks or epochs"), QuorumOpts),
+  responses(
+    (status = 200, description = "Under-signed or borderline blocks or elections, latest first", body = Vec<QuorumEntry>),
+    (status = 400, description = "Invalid kind", body = ErrorResp)
+  )
+)]
+#[get("/quorum/{kind}")]
+async fn list_quorum(path: web::Path<String>, params: web::Query<QuorumOpts>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let borderline = params.borderline.unwrap_or(0.05).clamp(0.0, 1.0);
+  let count = params.count.unwrap_or(100).clamp(1, 100);
+  let (collection, id_field, cid_field, height_field) = match path.into_inner().as_str() {
+    "blocks" => (ctx.vsc_db.blocks.clone_with_type::<Document>(), "be_info.block_id", "$block", "$slot_height"),
+    "epochs" => (ctx.vsc_db.elections.clone_with_type::<Document>(), "epoch", "$data", "$block_height"),
+    _ => {
+      return Err(RespErr::BadRequest { msg: String::from("Invalid kind") });
+    }
+  };
+  let mut filter = doc! {
+    "be_info.quorum_margin": { "$type": "number" },
+    "$expr": { "$lt": ["$be_info.quorum_margin", { "$multiply": ["$be_info.eligible_weight", borderline] }] }
+  };
+  if let Some(last_id) = params.last_id {
+    filter.insert(id_field, doc! { "$lte": last_id });
+  }
+  let pipeline = vec![
+    doc! { "$match": filter },
+    doc! { "$sort": { id_field: -1 } },
+    doc! { "$limit": count },
+    doc! {
+      "$project": {
+        "_id": 0,
+        "id": format!("${}", id_field),
+        "cid": cid_field,
+        "height": height_field,
+        "voted_weight": "$be_info.voted_weight",
+        "eligible_weight": "$be_info.eligible_weight",
+        "quorum_met": "$be_info.quorum_met",
+        "quorum_margin": "$be_info.quorum_margin"
+      }
+    }
+  ];
+  let mut cursor = collection
+    .aggregate(pipeline)
+    .with_type::<QuorumEntry>().await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  let mut results = Vec::new();
+  while let Some(doc) = cursor.next().await {
+    results.push(doc.map_err(|e| RespErr::DbErr { msg: e.to_string() })?);
   }
+  Ok(HttpResponse::Ok().json(results))
 }
 
+#[utoipa::path(
+  tag = "be-api",
+  params(("trx_id" = String, Path, description = "Hive transaction id or L2 transaction CID")),
+  responses(
+    (status = 200, description = "Operation outputs of a Hive transaction or the result of an L2 transaction", body = TxOutput),
+    (status = 400, description = "Transaction does not exist", body = ErrorResp),
+    (status = 404, description = "L2 transaction not found", body = ErrorResp)
+  )
+)]
 #[get("/tx/{trx_id}/output")]
 async fn get_tx_output(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let trx_id = path.into_inner();
@@ -333,17 +889,182 @@ async fn get_tx_output(path: web::Path<String>, ctx: web::Data<Context>) -> Resu
         result.push(None);
       }
     }
-    Ok(HttpResponse::Ok().json(result))
+    Ok(HttpResponse::Ok().json(TxOutput::Hive(result)))
   } else {
-    Err(RespErr::InternalErr { msg: String::from("L2 transaction outputs are currently WIP") })
+    let tx = match
+      ctx.vsc_db.tx_pool.find_one(doc! { "id": &trx_id }).await.map_err(|e| RespErr::DbErr { msg: e.to_string() })?
+    {
+      Some(tx) => tx,
+      None => {
+        return Ok(HttpResponse::NotFound().json(ErrorResp::new("transaction not found")));
+      }
+    };
+    let required_auths = tx.required_auths.unwrap_or_default();
+    let mut rc_used = Vec::new();
+    if let Some(height) = tx.anchored_height {
+      let opt = FindOneOptions::builder()
+        .sort(doc! { "block_height": -1 })
+        .build();
+      for account in required_auths.iter() {
+        let rc = ctx.vsc_db.rc
+          .find_one(doc! { "account": account, "block_height": doc! {"$lte": height as i64} })
+          .with_options(opt.clone()).await
+          .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+        rc_used.push(AccountRcUsed { account: account.clone(), amount: rc.map(|r| r.amount).unwrap_or(0) });
+      }
+    }
+    let anchor = match (tx.anchored_block, tx.anchored_id, tx.anchored_height) {
+      (Some(block), Some(tx_id), Some(height)) =>
+        Some(TxAnchor {
+          block,
+          tx_id,
+          height,
+          index: tx.anchored_index.unwrap_or(0),
+          op_index: tx.anchored_opidx.unwrap_or(0),
+        }),
+      _ => None,
+    };
+    Ok(
+      HttpResponse::Ok().json(
+        TxOutput::L2(
+          Box::new(L2TxOutput {
+            id: tx.id,
+            status: tx.status,
+            required_auths,
+            nonce: tx.nonce,
+            anchor,
+            ledger: tx.ledger.unwrap_or_default(),
+            output: tx.output,
+            rc_limit: tx.rc_limit,
+            rc_used,
+          })
+        )
+      )
+    )
   }
 }
 
-#[derive(Deserialize)]
+/// Page of `tx_pool` transactions matching `filter` after `cursor`, pending transactions first followed by the latest anchored.
+///
+/// Pending (`anchr_height` unset) and anchored transactions are fetched by two queries sorted on the stored fields, backed
+/// by the `{ anchr_height: -1, id: -1 }` and `{ "data.contract_id": 1, anchr_height: -1, id: -1 }` indexes created in
+/// `MongoDB::init`.
+async fn tx_page(ctx: &Context, filter: Document, cursor: &Option<String>, count: i64) -> Result<TxList, RespErr> {
+  let cursor = match cursor {
+    Some(cursor) =>
+      Some(
+        cursor
+          .split_once('.')
+          .and_then(|(h, id)| h.parse::<i64>().ok().map(|h| (h, id.to_string())))
+          .ok_or(RespErr::BadRequest { msg: String::from("Invalid cursor") })?
+      ),
+    None => None,
+  };
+  let mut txs: Vec<TransactionRecord> = Vec::new();
+  // pending transactions sort before every anchored one, a block range filter excludes them
+  let pending_page = cursor.as_ref().is_none_or(|(height, _)| *height == i64::MAX);
+  if pending_page && !filter.contains_key("anchr_height") {
+    let mut pending = filter.clone();
+    pending.insert("anchr_height", Bson::Null);
+    if let Some((_, id)) = &cursor {
+      pending.insert("id", doc! { "$lt": id });
+    }
+    let mut pending_cursor = ctx.vsc_db.tx_pool
+      .find(pending)
+      .sort(doc! { "id": -1 })
+      .limit(count).await
+      .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+    while let Some(tx) = pending_cursor.next().await {
+      txs.push(tx.map_err(|e| RespErr::DbErr { msg: e.to_string() })?);
+    }
+  }
+  if (txs.len() as i64) < count {
+    let page = match &cursor {
+      Some((height, id)) if *height != i64::MAX =>
+        doc! { "$or": [{ "anchr_height": { "$lt": height } }, { "anchr_height": height, "id": { "$lt": id } }] },
+      _ => doc! { "anchr_height": { "$ne": Bson::Null } },
+    };
+    let mut anchored_cursor = ctx.vsc_db.tx_pool
+      .find(doc! { "$and": [filter, page] })
+      .sort(doc! { "anchr_height": -1, "id": -1 })
+      .limit(count - (txs.len() as i64)).await
+      .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+    while let Some(tx) = anchored_cursor.next().await {
+      txs.push(tx.map_err(|e| RespErr::DbErr { msg: e.to_string() })?);
+    }
+  }
+  let next_cursor = match txs.last() {
+    Some(last) if (txs.len() as i64) == count =>
+      Some(format!("{}.{}", last.anchored_height.map(|h| h as i64).unwrap_or(i64::MAX), last.id)),
+    _ => None,
+  };
+  Ok(TxList { txs, next_cursor })
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ListTxsOpts {
+  /// next_cursor of the previous page
+  cursor: Option<String>,
+  /// Number of transactions, at most 100
+  count: Option<i64>,
+  /// Account prefixed by hive: or did: that signed the transaction
+  required_auth: Option<String>,
+  status: Option<String>,
+  /// Op type, i.e. call, transfer or withdraw
+  #[serde(rename = "type")]
+  #[param(rename = "type")]
+  op_type: Option<String>,
+  contract_id: Option<String>,
+  from_block: Option<i64>,
+  to_block: Option<i64>,
+}
+
+#[utoipa::path(
+  tag = "be-api",
+  params(ListTxsOpts),
+  responses(
+    (status = 200, description = "Transactions, pending transactions first followed by the latest anchored", body = TxList),
+    (status = 400, description = "Invalid cursor", body = ErrorResp)
+  )
+)]
+#[get("/txs")]
+async fn list_txs(params: web::Query<ListTxsOpts>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let count = params.count.unwrap_or(100).clamp(1, 100);
+  let mut filter = doc! {};
+  if let Some(required_auth) = &params.required_auth {
+    filter.insert("required_auths", required_auth);
+  }
+  if let Some(status) = &params.status {
+    filter.insert("status", status);
+  }
+  if let Some(op_type) = &params.op_type {
+    filter.insert("data.type", op_type);
+  }
+  if let Some(contract_id) = &params.contract_id {
+    filter.insert("data.contract_id", contract_id);
+  }
+  let mut height_range = doc! {};
+  if let Some(from_block) = params.from_block {
+    height_range.insert("$gte", from_block);
+  }
+  if let Some(to_block) = params.to_block {
+    height_range.insert("$lte", to_block);
+  }
+  if !height_range.is_empty() {
+    filter.insert("anchr_height", height_range);
+  }
+  Ok(HttpResponse::Ok().json(tx_page(&ctx, filter, &params.cursor, count).await?))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct ListContractsOpts {
+  /// Number of contracts, at most 200
   count: Option<i64>,
 }
 
+#[utoipa::path(tag = "be-api", params(ListContractsOpts), responses((status = 200, description = "Contracts, latest first", body = Vec<Contract>)))]
 #[get("/contracts")]
 async fn list_contracts(params: web::Query<ListContractsOpts>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let count = min(max(1, params.count.unwrap_or(100)), 200);
@@ -362,34 +1083,446 @@ async fn list_contracts(params: web::Query<ListContractsOpts>, ctx: web::Data<Co
   Ok(HttpResponse::Ok().json(results))
 }
 
+#[utoipa::path(
+  tag = "be-api",
+  params(("id" = String, Path, description = "Contract id")),
+  responses((status = 200, description = "Contract with its verifier submitted metadata", body = ContractDetail), (status = 404, description = "Contract does not exist", body = ErrorResp))
+)]
 #[get("/contract/{id}")]
 async fn get_contract(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let id = path.into_inner();
   let contract = ctx.vsc_db.contracts.find_one(doc! { "id": &id }).await.map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
   match contract {
-    Some(c) => { Ok(HttpResponse::Ok().json(c)) }
-    None => Ok(HttpResponse::NotFound().json(json!({"error": "Contract does not exist"}))),
+    Some(c) => {
+      let metadata = cv_api::latest_metadata(&ctx.db, &id).await?;
+      let stats = contract_stats(&ctx, &id).await?;
+      Ok(HttpResponse::Ok().json(ContractDetail { contract: c, metadata, stats }))
+    }
+    None => Ok(HttpResponse::NotFound().json(ErrorResp::new("Contract does not exist"))),
+  }
+}
+
+/// Statuses of anchored transactions in `tx_pool`
+const TX_CONFIRMED: &str = "CONFIRMED";
+const TX_FAILED: &str = "FAILED";
+
+/// `vsc.call` operations are indexed into `tx_pool` with `data.type` set to `call`.
+fn contract_calls(id: &str) -> Document {
+  doc! { "data.type": "call", "data.contract_id": id }
+}
+
+fn count_status(status: &str) -> Document {
+  doc! { "$sum": { "$cond": [{ "$eq": ["$status", status] }, 1, 0] } }
+}
+
+#[derive(Deserialize)]
+struct CallTotals {
+  calls: u64,
+  successful: u64,
+  failed: u64,
+  pending: u64,
+  last_call_height: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct CallerCount {
+  count: u64,
+}
+
+#[derive(Deserialize)]
+struct CallStatsFacet {
+  totals: Vec<CallTotals>,
+  callers: Vec<CallerCount>,
+}
+
+async fn contract_stats(ctx: &Context, id: &str) -> Result<ContractStats, RespErr> {
+  let pipeline = vec![
+    doc! { "$match": contract_calls(id) },
+    doc! {
+      "$facet": {
+        "totals": [{
+          "$group": {
+            "_id": Bson::Null,
+            "calls": { "$sum": 1 },
+            "successful": count_status(TX_CONFIRMED),
+            "failed": count_status(TX_FAILED),
+            // unset or null like the pending query of tx_page
+            "pending": { "$sum": { "$cond": [{ "$in": [{ "$type": "$anchr_height" }, ["missing", "null"]] }, 1, 0] } },
+            "last_call_height": { "$max": "$anchr_height" }
+          }
+        }],
+        "callers": [
+          { "$unwind": "$required_auths" },
+          { "$group": { "_id": "$required_auths" } },
+          { "$count": "count" }
+        ]
+      }
+    }
+  ];
+  let facet = ctx.vsc_db.tx_pool
+    .aggregate(pipeline)
+    .with_type::<CallStatsFacet>().await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?
+    .next().await
+    .transpose()
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  let (totals, unique_callers) = match facet {
+    Some(f) => (f.totals.into_iter().next(), f.callers.first().map(|c| c.count).unwrap_or(0)),
+    None => (None, 0),
+  };
+  let totals = totals.unwrap_or(CallTotals { calls: 0, successful: 0, failed: 0, pending: 0, last_call_height: None });
+  let settled = totals.successful + totals.failed;
+  let rate = |n: u64| if settled > 0 { Some((n as f64) / (settled as f64)) } else { None };
+  Ok(ContractStats {
+    calls: totals.calls,
+    unique_callers,
+    successful: totals.successful,
+    failed: totals.failed,
+    pending: totals.pending,
+    success_rate: rate(totals.successful),
+    failure_rate: rate(totals.failed),
+    last_call_height: totals.last_call_height,
+  })
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ContractCallsOpts {
+  /// next_cursor of the previous page
+  cursor: Option<String>,
+  /// Number of calls, at most 100
+  count: Option<i64>,
+  /// Account prefixed by hive: or did: that called the contract
+  caller: Option<String>,
+  status: Option<String>,
+}
+
+#[utoipa::path(
+  tag = "be-api",
+  params(("id" = String, Path, description = "Contract id"), ContractCallsOpts),
+  responses(
+    (status = 200, description = "Calls of the contract, pending calls first followed by the latest anchored", body = TxList),
+    (status = 400, description = "Invalid cursor", body = ErrorResp)
+  )
+)]
+#[get("/contract/{id}/calls")]
+async fn get_contract_calls(path: web::Path<String>, params: web::Query<ContractCallsOpts>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let count = params.count.unwrap_or(100).clamp(1, 100);
+  let mut filter = contract_calls(&path.into_inner());
+  if let Some(caller) = &params.caller {
+    filter.insert("required_auths", caller);
+  }
+  if let Some(status) = &params.status {
+    filter.insert("status", status);
+  }
+  Ok(HttpResponse::Ok().json(tx_page(&ctx, filter, &params.cursor, count).await?))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ContractCallersOpts {
+  /// Number of callers, at most 100
+  count: Option<i64>,
+}
+
+#[utoipa::path(
+  tag = "be-api",
+  params(("id" = String, Path, description = "Contract id"), ContractCallersOpts),
+  responses((status = 200, description = "Callers of the contract by descending number of calls", body = Vec<ContractCaller>))
+)]
+#[get("/contract/{id}/callers")]
+async fn get_contract_callers(path: web::Path<String>, params: web::Query<ContractCallersOpts>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let count = params.count.unwrap_or(100).clamp(1, 100);
+  let pipeline = vec![
+    doc! { "$match": contract_calls(&path.into_inner()) },
+    doc! { "$unwind": "$required_auths" },
+    doc! {
+      "$group": {
+        "_id": "$required_auths",
+        "calls": { "$sum": 1 },
+        "successful": count_status(TX_CONFIRMED),
+        "failed": count_status(TX_FAILED),
+        "first_height": { "$min": "$anchr_height" },
+        "last_height": { "$max": "$anchr_height" }
+      }
+    },
+    doc! { "$sort": { "calls": -1, "_id": 1 } },
+    doc! { "$limit": count },
+    doc! { "$addFields": { "caller": "$_id" } },
+    doc! { "$project": { "_id": 0 } }
+  ];
+  let mut cursor = ctx.vsc_db.tx_pool
+    .aggregate(pipeline)
+    .with_type::<ContractCaller>().await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  let mut results = Vec::new();
+  while let Some(doc) = cursor.next().await {
+    results.push(doc.map_err(|e| RespErr::DbErr { msg: e.to_string() })?);
   }
+  Ok(HttpResponse::Ok().json(results))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ContractActivityOpts {
+  /// Interval size in Hive blocks, at least 1200 (one hour). Defaults to 28800 (one day).
+  interval: Option<i64>,
+  /// Number of intervals, at most 365
+  count: Option<i64>,
+  /// Latest block height to include
+  to_block: Option<i64>,
 }
 
+#[utoipa::path(
+  tag = "be-api",
+  params(("id" = String, Path, description = "Contract id"), ContractActivityOpts),
+  responses((status = 200, description = "Anchored calls of the contract by block height interval, latest first. Intervals without calls are omitted.", body = Vec<ContractActivity>))
+)]
+#[get("/contract/{id}/activity")]
+async fn get_contract_activity(path: web::Path<String>, params: web::Query<ContractActivityOpts>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let interval = max(params.interval.unwrap_or(28800), 1200);
+  let count = params.count.unwrap_or(30).clamp(1, 365);
+  let mut filter = contract_calls(&path.into_inner());
+  match params.to_block {
+    Some(to_block) => filter.insert("anchr_height", doc! { "$exists": true, "$lte": to_block }),
+    None => filter.insert("anchr_height", doc! { "$exists": true }),
+  };
+  let pipeline = vec![
+    doc! { "$match": filter },
+    doc! {
+      "$group": {
+        "_id": { "$subtract": ["$anchr_height", { "$mod": ["$anchr_height", interval] }] },
+        "calls": { "$sum": 1 },
+        "callers": { "$addToSet": "$required_auths" },
+        "successful": count_status(TX_CONFIRMED),
+        "failed": count_status(TX_FAILED)
+      }
+    },
+    doc! { "$sort": { "_id": -1 } },
+    doc! { "$limit": count },
+    doc! {
+      "$project": {
+        "_id": 0,
+        "height": "$_id",
+        "calls": 1,
+        "unique_callers": {
+          "$size": { "$reduce": { "input": "$callers", "initialValue": [], "in": { "$setUnion": ["$$value", { "$ifNull": ["$$this", []] }] } } }
+        },
+        "successful": 1,
+        "failed": 1
+      }
+    }
+  ];
+  let mut cursor = ctx.vsc_db.tx_pool
+    .aggregate(pipeline)
+    .with_type::<ContractActivity>().await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  let mut results = Vec::new();
+  while let Some(doc) = cursor.next().await {
+    results.push(doc.map_err(|e| RespErr::DbErr { msg: e.to_string() })?);
+  }
+  Ok(HttpResponse::Ok().json(results))
+}
+
+#[utoipa::path(
+  tag = "be-api",
+  params(("query" = String, Path, description = "Block CID, election data CID, contract id or transaction id")),
+  responses((status = 200, description = "Type of the matching entity and its identifier", body = SearchResult))
+)]
 #[get("/search/{query}")]
 async fn search(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let query = path.into_inner();
   let block = ctx.vsc_db.blocks.find_one(doc! { "block": &query }).await.map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
   if block.is_some() {
-    return Ok(HttpResponse::Ok().json(json!({"type": "block", "result": &query})));
+    return Ok(HttpResponse::Ok().json(SearchResult { r#type: String::from("block"), result: SearchValue::Id(query) }));
   }
   let election = ctx.vsc_db.elections.find_one(doc! { "data": &query }).await.map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
   if election.is_some() {
-    return Ok(HttpResponse::Ok().json(json!({"type": "election", "result": election.unwrap().epoch})));
+    return Ok(HttpResponse::Ok().json(SearchResult { r#type: String::from("election"), result: SearchValue::Epoch(election.unwrap().epoch) }));
   }
   let contract = ctx.vsc_db.contracts.find_one(doc! { "id": &query }).await.map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
   if contract.is_some() {
-    return Ok(HttpResponse::Ok().json(json!({"type": "contract", "result": &query})));
+    return Ok(HttpResponse::Ok().json(SearchResult { r#type: String::from("contract"), result: SearchValue::Id(query) }));
   }
   let tx = ctx.vsc_db.tx_pool.find_one(doc! { "id": &query }).await.map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
   if tx.is_some() {
-    return Ok(HttpResponse::Ok().json(json!({"type": "tx", "result": &query})));
+    return Ok(HttpResponse::Ok().json(SearchResult { r#type: String::from("tx"), result: SearchValue::Id(query) }));
+  }
+  Ok(HttpResponse::Ok().json(SearchResult { r#type: String::new(), result: SearchValue::Id(String::new()) }))
+}
+
+#[utoipa::path(
+  tag = "be-api",
+  request_body(content = Object, description = "GraphQL request with query, variables and operationName"),
+  responses((status = 200, description = "GraphQL response with data and errors", body = Object))
+)]
+#[post("/graphql")]
+async fn graphql(req: web::Json<async_graphql::Request>, ctx: web::Data<Context>) -> impl Responder {
+  HttpResponse::Ok().json(ctx.gql_schema.execute(req.into_inner()).await)
+}
+
+#[utoipa::path(tag = "be-api", responses((status = 200, description = "GraphQL playground", body = String, content_type = "text/html")))]
+#[get("/graphql")]
+async fn graphql_playground() -> impl Responder {
+  HttpResponse::Ok()
+    .content_type("text/html; charset=utf-8")
+    .body(playground_source(GraphQLPlaygroundConfig::new("/be-api/v1/graphql")))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+  paths(
+    hello,
+    props,
+    list_witnesses,
+    get_witness,
+    get_witness_stats,
+    get_balance,
+    get_balance_history,
+    get_account_history,
+    list_epochs,
+    get_epoch,
+    get_epoch_diff,
+    list_blocks,
+    get_block,
+    list_quorum,
+    get_tx_output,
+    list_txs,
+    list_contracts,
+    get_contract,
+    get_contract_calls,
+    get_contract_callers,
+    get_contract_activity,
+    search,
+    graphql,
+    graphql_playground
+  )
+)]
+pub struct BeApiDoc;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+  use crate::types::vsc::ElectionMember;
+
+  fn election(epoch: i64, members: &[(&str, u64)], protocol_version: u64) -> ElectionResultRecord {
+    ElectionResultRecord {
+      epoch,
+      net_id: String::from("vsc-mainnet"),
+      data: String::new(),
+      members: members
+        .iter()
+        .map(|(account, _)| ElectionMember { key: format!("did:key:{}", account), account: account.to_string() })
+        .collect(),
+      weights: members
+        .iter()
+        .map(|(_, w)| *w)
+        .collect(),
+      protocol_version,
+      total_weight: members
+        .iter()
+        .map(|(_, w)| *w)
+        .sum(),
+      block_height: 90000000 + (epoch as u64) * 7200,
+      proposer: String::from("alice"),
+      tx_id: String::new(),
+      r#type: String::from("initial"),
+      be_info: None,
+    }
+  }
+
+  fn diff(prev: Option<&ElectionResultRecord>, cur: &ElectionResultRecord) -> Value {
+    serde_json::to_value(election_diff(prev, cur)).unwrap()
+  }
+
+  #[test]
+  fn test_first_election() {
+    let cur = election(0, &[("alice", 10), ("bob", 20)], 0);
+    assert_eq!(
+      diff(None, &cur),
+      json!({
+        "previous_epoch": null,
+        "joined": ["alice", "bob"],
+        "left": [],
+        "weight_changes": [],
+        "total_weight_change": 30,
+        "protocol_version": null
+      })
+    );
+  }
+
+  #[test]
+  fn test_unchanged() {
+    let prev = election(4, &[("alice", 10), ("bob", 20)], 1);
+    let cur = election(5, &[("bob", 20), ("alice", 10)], 1);
+    assert_eq!(
+      diff(Some(&prev), &cur),
+      json!({
+        "previous_epoch": 4,
+        "joined": [],
+        "left": [],
+        "weight_changes": [],
+        "total_weight_change": 0,
+        "protocol_version": null
+      })
+    );
+  }
+
+  #[test]
+  fn test_member_joined() {
+    let prev = election(4, &[("alice", 10), ("bob", 20)], 1);
+    let cur = election(5, &[("alice", 10), ("bob", 20), ("carol", 5)], 1);
+    let d = diff(Some(&prev), &cur);
+    assert_eq!(d["joined"], json!(["carol"]));
+    assert_eq!(d["left"], json!([]));
+    assert_eq!(d["weight_changes"], json!([]));
+    assert_eq!(d["total_weight_change"], 5);
+  }
+
+  #[test]
+  fn test_member_left() {
+    let prev = election(4, &[("alice", 10), ("bob", 20), ("carol", 5)], 1);
+    let cur = election(5, &[("alice", 10), ("carol", 5)], 1);
+    let d = diff(Some(&prev), &cur);
+    assert_eq!(d["joined"], json!([]));
+    assert_eq!(d["left"], json!(["bob"]));
+    assert_eq!(d["weight_changes"], json!([]));
+    assert_eq!(d["total_weight_change"], -20);
+  }
+
+  #[test]
+  fn test_weight_changes() {
+    let prev = election(4, &[("alice", 10), ("bob", 20), ("carol", 5)], 1);
+    let cur = election(5, &[("alice", 15), ("bob", 12), ("carol", 5)], 1);
+    let d = diff(Some(&prev), &cur);
+    assert_eq!(d["joined"], json!([]));
+    assert_eq!(d["left"], json!([]));
+    assert_eq!(
+      d["weight_changes"],
+      json!([
+        { "account": "alice", "previous": 10, "current": 15, "change": 5 },
+        { "account": "bob", "previous": 20, "current": 12, "change": -8 }
+      ])
+    );
+    assert_eq!(d["total_weight_change"], -3);
+  }
+
+  #[test]
+  fn test_missing_weight() {
+    let prev = election(4, &[("alice", 10)], 1);
+    let mut cur = election(5, &[("alice", 10)], 1);
+    cur.weights.clear();
+    let d = diff(Some(&prev), &cur);
+    assert_eq!(d["weight_changes"], json!([{ "account": "alice", "previous": 10, "current": 0, "change": -10 }]));
+  }
+
+  #[test]
+  fn test_protocol_version_change() {
+    let prev = election(4, &[("alice", 10)], 1);
+    let cur = election(5, &[("alice", 10)], 2);
+    let d = diff(Some(&prev), &cur);
+    assert_eq!(d["protocol_version"], json!({ "previous": 1, "current": 2 }));
+    assert_eq!(d["total_weight_change"], 0);
   }
-  Ok(HttpResponse::Ok().json(json!({"type": "", "result": ""})))
 }
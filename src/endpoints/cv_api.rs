@@ -1,94 +1,89 @@
-use actix_web::{ get, post, web, HttpRequest, HttpResponse, Responder };
+use actix_web::{ delete, get, post, web, HttpRequest, HttpResponse, Responder };
 use actix_multipart::form::{ tempfile::TempFile, MultipartForm, text::Text };
 use mongodb::bson::doc;
 use tokio_postgres::types::Type;
-use serde::{ Serialize, Deserialize };
+use serde::Deserialize;
+use utoipa::{ IntoParams, OpenApi };
 use serde_json::{ json, Number, Value };
 use semver::VersionReq;
 use chrono::{ NaiveDateTime, Utc, Duration };
 use hex;
 use sha2::{ Sha256, Digest };
-use jsonwebtoken::{ Header, EncodingKey, DecodingKey, Algorithm, Validation, errors::ErrorKind };
+use rand::Rng;
 use log::{ error, debug };
-use std::io::Read;
-use crate::{ config::config, constants::*, types::{ server::{ Context, RespErr }, hive::{ JsonRpcResp, DgpAtBlock } } };
+use std::{ collections::{ BTreeMap, HashMap }, io::Read };
+use sig_verify::evm;
+use crate::{
+  abi::Abi,
+  auth,
+  authority::SigCheck,
+  bundle,
+  config::config,
+  db::DbPool,
+  license::{ self, LicenseTexts },
+  metadata::ContractMetadata,
+  receipt::receipt_key,
+  constants::*,
+  types::{ cv::*, server::{ Context, RespErr, ErrorResp, SuccessResp }, hive::{ JsonRpcResp, DgpAtBlock } },
+};
 
+#[utoipa::path(tag = "cv-api", responses((status = 200, description = "Hello world", body = String, content_type = "text/plain")))]
 #[get("")]
 async fn hello() -> impl Responder {
   HttpResponse::Ok().body("Hello world!")
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Claims {
-  user: String,
-  app: String,
-  network: String,
-  iat: i64, // Issued at (timestamp)
-  exp: i64, // Expiration time (timestamp)
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct LoginChallengeOpts {
+  /// Hive username or EVM address that will sign the login message
+  account: String,
 }
 
-fn verify_auth_token(req: &HttpRequest) -> Result<String, RespErr> {
-  if config.auth.enabled {
-    if let Some(auth_header) = req.clone().headers().get("Authorization") {
-      let auth_value = auth_header.to_str().unwrap_or("");
-      let parts = auth_value.split(" ").collect::<Vec<&str>>();
-      debug!("Authentication header: {}", auth_value);
-      if parts.len() < 2 || parts[0] != "Bearer" {
-        return Err(RespErr::TokenMissing);
-      }
-      let mut validation = Validation::new(Algorithm::HS256);
-      validation.validate_exp = true;
-      validation.leeway = 0;
-      let claims = (match
-        jsonwebtoken::decode::<Claims>(
-          parts[1],
-          &DecodingKey::from_secret(hex::decode(config.auth.key.clone().unwrap()).unwrap().as_slice()),
-          &validation
-        )
-      {
-        Ok(token_data) => {
-          // Additional manual checks if needed
-          let now = Utc::now().timestamp();
-
-          // Verify iat is in the past
-          if token_data.claims.iat > now {
-            return Err(RespErr::TokenExpired);
-          }
-
-          Ok(token_data.claims)
-        }
-        Err(err) =>
-          match err.kind() {
-            ErrorKind::ExpiredSignature => Err(RespErr::TokenExpired),
-            _ => Err(RespErr::TokenInvalid),
-          }
-      })?;
-      return Ok(claims.user);
-    } else {
-      return Err(RespErr::TokenMissing);
-    }
-  }
-  Ok(String::from(""))
-}
-
-#[post("/login")]
-async fn login(payload: String, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+#[utoipa::path(
+  tag = "auth",
+  params(LoginChallengeOpts),
+  responses(
+    (status = 200, description = "Single use nonce to be included in the login message signed by the account", body = LoginChallenge),
+    (status = 400, description = "Invalid account", body = ErrorResp),
+    (status = 404, description = "Auth is disabled", body = ErrorResp),
+    (status = 429, description = "Too many outstanding challenges for the account", body = ErrorResp)
+  )
+)]
+#[get("/login/challenge")]
+async fn login_challenge(params: web::Query<LoginChallengeOpts>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   if !config.auth.enabled {
-    return Ok(HttpResponse::NotFound().json(json!({"error": "Auth is disabled"})));
+    return Ok(HttpResponse::NotFound().json(ErrorResp::new("Auth is disabled")));
   }
-  let parts: Vec<&str> = payload.split(":").collect();
-  if parts.len() != 6 || parts[1] != &config.auth.id.clone().unwrap() || parts[2] != "hive" {
-    return Err(RespErr::BadRequest { msg: String::from("Invalid auth message format") });
+  let account = params.account.trim().to_ascii_lowercase();
+  if account.is_empty() || account.len() > 68 {
+    return Err(RespErr::BadRequest { msg: String::from("Invalid account") });
   }
-  let block_num = parts[3].parse::<u64>();
-  if block_num.is_err() {
-    return Err(RespErr::BadRequest { msg: String::from("Could not parse block number") });
+  let nonce = hex::encode(rand::rng().random::<[u8; 16]>());
+  let now = Utc::now().naive_utc();
+  let expiry = now + Duration::seconds(config.auth.challenge_timeout.unwrap_or(300) as i64);
+  let max_challenges = config.auth.max_challenges.unwrap_or(5);
+  ctx.db
+    .query("DELETE FROM vsc_cv.login_challenges WHERE expiry < $1;", &[(&now, Type::TIMESTAMP)]).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  let inserted = ctx.db
+    .query(
+      "INSERT INTO vsc_cv.login_challenges(nonce,account,expiry) SELECT $1,$2,$3 WHERE (SELECT COUNT(*) FROM vsc_cv.login_challenges WHERE account=$2) < $4 RETURNING nonce;",
+      &[
+        (&nonce, Type::VARCHAR),
+        (&account, Type::VARCHAR),
+        (&expiry, Type::TIMESTAMP),
+        (&max_challenges, Type::INT8),
+      ]
+    ).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  if inserted.is_empty() {
+    return Err(RespErr::ChallengeLimit);
   }
-  let block_num = block_num.unwrap();
-  let original = (&parts[0..5]).join(":");
-  let mut hasher = Sha256::new();
-  hasher.update(&original);
-  let hash = hex::encode(&hasher.finalize()[..]);
+  Ok(HttpResponse::Ok().json(LoginChallenge { nonce, expiry: expiry.format("%Y-%m-%dT%H:%M:%S%.6f").to_string() }))
+}
+
+async fn verify_signatures_rpc(ctx: &Context, user: &str, hash: &str, signatures: &[&str]) -> Result<bool, RespErr> {
   let verify_req = ctx.http_client
     .post(config.hive_rpc.clone())
     .json::<Value>(
@@ -97,11 +92,11 @@ async fn login(payload: String, ctx: web::Data<Context>) -> Result<HttpResponse,
     "jsonrpc": "2.0",
     "method": "database_api.verify_signatures",
     "params": {
-      "hash": &hash,
-      "signatures": [parts[5]],
+      "hash": hash,
+      "signatures": signatures,
       "required_owner": [],
       "required_active": [],
-      "required_posting": [parts[0]],
+      "required_posting": [user],
       "required_other": []
   }
   })
@@ -110,8 +105,30 @@ async fn login(payload: String, ctx: web::Data<Context>) -> Result<HttpResponse,
     .map_err(|_| RespErr::SigVerifyReqFail)?
     .json::<JsonRpcResp>().await
     .map_err(|_| RespErr::SigVerifyReqFail)?;
-  let is_valid =
-    !verify_req.error.is_some() && verify_req.result.is_some() && verify_req.result.unwrap().clone()["valid"].as_bool().unwrap();
+  Ok(verify_req.error.is_none() && verify_req.result.is_some_and(|r| r["valid"].as_bool().unwrap_or(false)))
+}
+
+/// Verify a Hive posting signature for `user:app:hive:block_num:block_hash:nonce:signature`
+async fn login_hive(parts: &[&str], ctx: &Context) -> Result<String, RespErr> {
+  if parts.len() != 7 {
+    return Err(RespErr::BadRequest { msg: String::from("Invalid auth message format") });
+  }
+  let block_num = parts[3].parse::<u64>();
+  if block_num.is_err() {
+    return Err(RespErr::BadRequest { msg: String::from("Could not parse block number") });
+  }
+  let block_num = block_num.unwrap();
+  let original = (&parts[0..6]).join(":");
+  let mut hasher = Sha256::new();
+  hasher.update(&original);
+  let digest: [u8; 32] = hasher.finalize().into();
+  // multisig posting authorities may provide several comma separated signatures
+  let signatures: Vec<&str> = parts[6].split(",").collect();
+  let is_valid = match ctx.authorities.verify_posting(parts[0], &digest, &signatures).await? {
+    SigCheck::Valid => true,
+    SigCheck::Invalid => false,
+    SigCheck::Unresolved => verify_signatures_rpc(ctx, parts[0], &hex::encode(digest), &signatures).await?,
+  };
   if !is_valid {
     return Err(RespErr::SigVerifyFail);
   }
@@ -133,32 +150,170 @@ async fn login(payload: String, ctx: web::Data<Context>) -> Result<HttpResponse,
   if &dgp_at_block.hash != parts[4] {
     return Err(RespErr::SigBhNotMatch);
   }
+  Ok(String::from(parts[0]))
+}
 
-  // generate jwt
-  let now = Utc::now();
-  let iat = now.timestamp();
-  let exp = (now + Duration::hours(1)).timestamp();
-  let claims = Claims {
-    user: String::from(parts[0]),
-    app: config.auth.id.clone().unwrap(),
-    network: String::from("hive"),
-    iat,
-    exp,
+/// Verify an EIP-191 `personal_sign` signature for `address:app:evm:nonce:signature`
+fn login_evm(parts: &[&str]) -> Result<String, RespErr> {
+  if parts.len() != 5 {
+    return Err(RespErr::BadRequest { msg: String::from("Invalid auth message format") });
+  }
+  let address = parts[0];
+  if address.len() != 42 || !address.starts_with("0x") || hex::decode(&address[2..]).is_err() {
+    return Err(RespErr::BadRequest { msg: String::from("Invalid EVM address") });
+  }
+  let original = parts[0..4].join(":");
+  let recovered = evm::recover_address(original.as_bytes(), parts[4]).map_err(|_| RespErr::SigVerifyFail)?;
+  if !recovered.eq_ignore_ascii_case(address) {
+    return Err(RespErr::SigVerifyFail);
+  }
+  Ok(format!("did:pkh:eip155:1:{}", recovered))
+}
+
+#[utoipa::path(
+  tag = "auth",
+  request_body(
+    content = String,
+    content_type = "text/plain",
+    description = "`user:app:hive:block_num:block_hash:nonce:signature` or `address:app:evm:nonce:signature`"
+  ),
+  responses(
+    (status = 200, description = "New session tokens", body = TokenResp),
+    (status = 400, description = "Invalid auth message", body = ErrorResp),
+    (status = 401, description = "Invalid signature or challenge", body = ErrorResp),
+    (status = 404, description = "Auth is disabled", body = ErrorResp)
+  )
+)]
+#[post("/login")]
+async fn login(payload: String, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  if !config.auth.enabled {
+    return Ok(HttpResponse::NotFound().json(ErrorResp::new("Auth is disabled")));
+  }
+  let parts: Vec<&str> = payload.split(":").collect();
+  if parts.len() < 5 || parts[1] != &config.auth.id.clone().unwrap() {
+    return Err(RespErr::BadRequest { msg: String::from("Invalid auth message format") });
+  }
+  let (user, network) = match parts[2] {
+    "hive" => (login_hive(&parts, &ctx).await?, "hive"),
+    "evm" => (login_evm(&parts)?, "evm"),
+    _ => {
+      return Err(RespErr::BadRequest { msg: String::from("Unsupported network") });
+    }
   };
-  let decoded_secret = hex::decode(config.auth.key.clone().unwrap()).map_err(|_| RespErr::TokenGenFail)?;
-  let token = jsonwebtoken
-    ::encode(&Header::default(), &claims, &EncodingKey::from_secret(&decoded_secret))
-    .map_err(|_| RespErr::TokenGenFail)?;
-  Ok(HttpResponse::Ok().json(json!({ "access_token": token })))
+  // consume the challenge so that the signed message cannot be replayed
+  auth::consume_challenge(&ctx.db, parts[parts.len() - 2], parts[0]).await?;
+  let tokens = auth::new_session(&ctx.db, &user, network).await?;
+  Ok(HttpResponse::Ok().json(TokenResp { access_token: tokens.access_token, refresh_token: tokens.refresh_token }))
+}
+
+#[utoipa::path(tag = "auth", responses((status = 200, description = "Public keys verifying access tokens", body = Jwks)))]
+#[get("/.well-known/jwks.json")]
+async fn jwks() -> impl Responder {
+  HttpResponse::Ok().json(auth::jwks())
+}
+
+#[utoipa::path(
+  tag = "auth",
+  request_body(content = String, content_type = "text/plain", description = "Refresh token"),
+  responses(
+    (status = 200, description = "Rotated session tokens", body = TokenResp),
+    (status = 401, description = "Invalid, expired or reused refresh token", body = ErrorResp),
+    (status = 404, description = "Auth is disabled", body = ErrorResp)
+  )
+)]
+#[post("/login/refresh")]
+async fn login_refresh(payload: String, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  if !config.auth.enabled {
+    return Ok(HttpResponse::NotFound().json(ErrorResp::new("Auth is disabled")));
+  }
+  let tokens = auth::refresh_session(&ctx.db, &payload).await?;
+  Ok(HttpResponse::Ok().json(TokenResp { access_token: tokens.access_token, refresh_token: tokens.refresh_token }))
+}
+
+#[utoipa::path(
+  tag = "auth",
+  security(("bearer" = [])),
+  responses((status = 200, description = "Session revoked", body = SuccessResp), (status = 401, description = "Unauthorized", body = ErrorResp))
+)]
+#[post("/logout")]
+async fn logout(req: HttpRequest, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let claims = auth::verify_auth_token(&req, &ctx.db).await?;
+  auth::revoke_session(&ctx.db, &claims.jti).await?;
+  Ok(HttpResponse::Ok().json(SuccessResp::ok()))
 }
 
-#[derive(Serialize, Deserialize)]
-struct ReqVerifyNew {
-  license: String,
-  lang: String,
-  dependencies: Value,
+#[utoipa::path(
+  tag = "auth",
+  security(("bearer" = [])),
+  responses((status = 200, description = "Active sessions of the user", body = Vec<Session>), (status = 401, description = "Unauthorized", body = ErrorResp))
+)]
+#[get("/sessions")]
+async fn list_sessions(req: HttpRequest, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let claims = auth::verify_auth_token(&req, &ctx.db).await?;
+  let rows = ctx.db
+    .query(
+      "SELECT id, network, created_ts, last_refresh_ts, expiry FROM vsc_cv.sessions WHERE username=$1 AND revoked=false AND expiry > $2 ORDER BY created_ts DESC;",
+      &[
+        (&claims.user, Type::VARCHAR),
+        (&Utc::now().naive_utc(), Type::TIMESTAMP),
+      ]
+    ).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  let result: Vec<Session> = rows
+    .iter()
+    .map(|s| Session {
+      id: s.get(0),
+      network: s.get(1),
+      created_ts: s.get::<usize, NaiveDateTime>(2).format("%Y-%m-%dT%H:%M:%S%.6f").to_string(),
+      last_refresh_ts: s.get::<usize, NaiveDateTime>(3).format("%Y-%m-%dT%H:%M:%S%.6f").to_string(),
+      expiry: s.get::<usize, NaiveDateTime>(4).format("%Y-%m-%dT%H:%M:%S%.6f").to_string(),
+      current: s.get::<usize, &str>(0) == claims.jti,
+    })
+    .collect();
+  Ok(HttpResponse::Ok().json(result))
+}
+
+#[utoipa::path(
+  tag = "auth",
+  security(("bearer" = [])),
+  params(("id" = String, Path, description = "Session id")),
+  responses(
+    (status = 200, description = "Session revoked", body = SuccessResp),
+    (status = 401, description = "Unauthorized", body = ErrorResp),
+    (status = 404, description = "Session not found", body = ErrorResp)
+  )
+)]
+#[delete("/sessions/{id}")]
+async fn revoke_session(path: web::Path<String>, req: HttpRequest, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let claims = auth::verify_auth_token(&req, &ctx.db).await?;
+  let session_id = path.into_inner();
+  let revoked = ctx.db
+    .query(
+      "UPDATE vsc_cv.sessions SET revoked=true WHERE id=$1 AND username=$2 RETURNING id;",
+      &[
+        (&session_id, Type::VARCHAR),
+        (&claims.user, Type::VARCHAR),
+      ]
+    ).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  if revoked.is_empty() {
+    return Ok(HttpResponse::NotFound().json(ErrorResp::new("session not found")));
+  }
+  Ok(HttpResponse::Ok().json(SuccessResp::ok()))
 }
 
+#[utoipa::path(
+  tag = "verification",
+  security(("bearer" = [])),
+  params(("address" = String, Path, description = "Contract address")),
+  request_body = ReqVerifyNew,
+  responses(
+    (status = 200, description = "Verification request created, sources may be uploaded", body = SuccessResp),
+    (status = 400, description = "Invalid request", body = ErrorResp),
+    (status = 401, description = "Unauthorized", body = ErrorResp),
+    (status = 404, description = "Contract not found", body = ErrorResp)
+  )
+)]
 #[post("/verify/{address}/new")]
 async fn verify_new(
   req: HttpRequest,
@@ -166,19 +321,25 @@ async fn verify_new(
   req_data: web::Json<ReqVerifyNew>,
   ctx: web::Data<Context>
 ) -> Result<HttpResponse, RespErr> {
-  let username = verify_auth_token(&req)?;
+  let username = auth::verify_auth_token(&req, &ctx.db).await?.user;
   let address = path.into_inner();
   let contract = ctx.vsc_db.contracts.find_one(doc! { "id": &address }).await.map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
   if contract.is_none() {
-    return Ok(HttpResponse::NotFound().json(json!({"error": "contract not found"})));
+    return Ok(HttpResponse::NotFound().json(ErrorResp::new("contract not found")));
   }
   let contract = contract.unwrap();
+  let custom_licenses: Vec<String> = ctx.db
+    .query("SELECT name FROM vsc_cv.licenses;", &[]).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?
+    .iter()
+    .map(|l| l.get(0))
+    .collect();
+  let license = license::validate_expression(&req_data.license, &custom_licenses).map_err(|msg| RespErr::BadRequest { msg })?;
   let can_verify: String = ctx.db
     .query(
-      "SELECT vsc_cv.can_verify_new($1,$2,$3);",
+      "SELECT vsc_cv.can_verify_new($1,$2);",
       &[
         (&address, Type::VARCHAR),
-        (&req_data.license, Type::VARCHAR),
         (&req_data.lang, Type::VARCHAR),
       ]
     ).await
@@ -236,13 +397,13 @@ async fn verify_new(
         (&contract.code, Type::VARCHAR),
         (&username, Type::VARCHAR),
         (&Utc::now().naive_utc(), Type::TIMESTAMP),
-        (&req_data.license, Type::VARCHAR),
+        (&license, Type::VARCHAR),
         (&req_data.lang, Type::VARCHAR),
         (&req_data.dependencies, Type::JSONB),
       ]
     ).await
     .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
-  Ok(HttpResponse::Ok().json(json!({ "success": true })))
+  Ok(HttpResponse::Ok().json(SuccessResp::ok()))
 }
 
 #[derive(Debug, MultipartForm)]
@@ -252,6 +413,17 @@ struct VerifUploadForm {
   filename: Text<String>,
 }
 
+#[utoipa::path(
+  tag = "verification",
+  security(("bearer" = [])),
+  params(("address" = String, Path, description = "Contract address")),
+  request_body(content = UploadFile, content_type = "multipart/form-data"),
+  responses(
+    (status = 200, description = "File uploaded", body = SuccessResp),
+    (status = 400, description = "Invalid file", body = ErrorResp),
+    (status = 401, description = "Unauthorized", body = ErrorResp)
+  )
+)]
 #[post("/verify/{address}/upload")]
 async fn upload_file(
   path: web::Path<String>,
@@ -259,7 +431,7 @@ async fn upload_file(
   MultipartForm(mut form): MultipartForm<VerifUploadForm>,
   ctx: web::Data<Context>
 ) -> Result<HttpResponse, RespErr> {
-  verify_auth_token(&req)?;
+  auth::verify_auth_token(&req, &ctx.db).await?;
   let address = path.into_inner();
   debug!("Uploaded file {} with size: {}", form.file.file_name.unwrap(), form.file.size);
   debug!("Contract address {}, new filename: {}", &address, &form.filename.0);
@@ -291,20 +463,31 @@ async fn upload_file(
   }
   ctx.db
     .query(
-      "INSERT INTO vsc_cv.source_code(contract_addr,fname,content) VALUES($1,$2,$3) ON CONFLICT(contract_addr,fname) DO UPDATE SET content=$3;",
+      "INSERT INTO vsc_cv.source_code(contract_addr,fname,content,sha256) VALUES($1,$2,$3,$4) ON CONFLICT(contract_addr,fname) DO UPDATE SET content=$3, sha256=$4;",
       &[
         (&address, Type::VARCHAR),
         (&form.filename.0, Type::VARCHAR),
         (&contents, Type::VARCHAR),
+        (&bundle::file_hash(&contents), Type::VARCHAR),
       ]
     ).await
     .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
-  Ok(HttpResponse::Ok().json(json!({ "success": true })))
+  Ok(HttpResponse::Ok().json(SuccessResp::ok()))
 }
 
+#[utoipa::path(
+  tag = "verification",
+  security(("bearer" = [])),
+  params(("address" = String, Path, description = "Contract address")),
+  responses(
+    (status = 200, description = "Verification queued", body = SuccessResp),
+    (status = 400, description = "Verification is not pending upload", body = ErrorResp),
+    (status = 401, description = "Unauthorized", body = ErrorResp)
+  )
+)]
 #[post("/verify/{address}/complete")]
 async fn upload_complete(path: web::Path<String>, req: HttpRequest, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
-  verify_auth_token(&req)?;
+  auth::verify_auth_token(&req, &ctx.db).await?;
   let address = path.into_inner();
   let contr = ctx.db
     .query("SELECT hive_username, status FROM vsc_cv.contracts WHERE contract_addr=$1;", &[(&address, Type::VARCHAR)]).await
@@ -326,92 +509,313 @@ async fn upload_complete(path: web::Path<String>, req: HttpRequest, ctx: web::Da
   ctx.db
     .query("UPDATE vsc_cv.contracts SET status=1::SMALLINT WHERE contract_addr=$1;", &[(&address, Type::VARCHAR)]).await
     .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  ctx.db
+    .query("SELECT vsc_cv.update_attempt($1,1::SMALLINT,NULL,NULL);", &[(&address, Type::VARCHAR)]).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
   ctx.compiler.notify();
   debug!("Complete");
-  Ok(HttpResponse::Ok().json(json!({ "success": true })))
+  Ok(HttpResponse::Ok().json(SuccessResp::ok()))
 }
 
+#[utoipa::path(
+  tag = "verification",
+  responses((status = 200, description = "Supported languages, null if none are configured", body = Option<Vec<String>>))
+)]
 #[get("/languages")]
 async fn list_langs(ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let rows = ctx.db
-    .query("SELECT jsonb_agg(name) FROM vsc_cv.languages;", &[]).await
+    .query("SELECT name FROM vsc_cv.languages ORDER BY id;", &[]).await
     .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
-  let result: Value = rows[0].get(0);
-  Ok(HttpResponse::Ok().json(result))
+  let result: Vec<&str> = rows
+    .iter()
+    .map(|l| l.get(0))
+    .collect();
+  Ok(HttpResponse::Ok().json((!result.is_empty()).then_some(result)))
 }
 
+#[utoipa::path(
+  tag = "verification",
+  responses((status = 200, description = "Supported SPDX license identifiers and custom LicenseRef- identifiers", body = Vec<String>))
+)]
 #[get("/licenses")]
 async fn list_licenses(ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let rows = ctx.db
-    .query("SELECT jsonb_agg(name) FROM vsc_cv.licenses;", &[]).await
+    .query("SELECT name FROM vsc_cv.licenses ORDER BY id;", &[]).await
     .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
-  let result: Value = rows[0].get(0);
+  let mut result: Vec<&str> = license::spdx_ids();
+  result.extend(rows.iter().map(|l| l.get::<usize, &str>(0)));
   Ok(HttpResponse::Ok().json(result))
 }
 
+#[utoipa::path(
+  tag = "contracts",
+  params(("address" = String, Path, description = "Contract address")),
+  responses((status = 200, description = "Verification details", body = ContractInfo), (status = 404, description = "Contract not found", body = ErrorResp))
+)]
 #[get("/contract/{address}")]
 async fn contract_info(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let addr = path.into_inner();
   let contract = ctx.db
     .query(
-      "SELECT c.bytecode_cid, c.hive_username, c.request_ts, c.verified_ts, s.name, c.exports, lc.name, lg.name, c.dependencies FROM vsc_cv.contracts c JOIN vsc_cv.status s ON s.id = c.status JOIN vsc_cv.licenses lc ON lc.id = c.license JOIN vsc_cv.languages lg ON lg.id = c.lang WHERE contract_addr=$1;",
+      "SELECT c.bytecode_cid, c.hive_username, c.request_ts, c.verified_ts, s.name, c.exports, c.license, lg.name, c.dependencies, c.hidden, c.abi FROM vsc_cv.contracts c JOIN vsc_cv.status s ON s.id = c.status JOIN vsc_cv.languages lg ON lg.id = c.lang WHERE contract_addr=$1;",
       &[(&addr, Type::VARCHAR)]
     ).await
     .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
   if contract.len() == 0 {
-    return Ok(HttpResponse::NotFound().json(json!({"error": "contract not found"})));
+    return Ok(HttpResponse::NotFound().json(ErrorResp::new("contract not found")));
   }
-  let files = ctx.db
+  let files = source_filenames(&ctx, &addr).await?;
+  let lockfilename = ctx.db
     .query(
-      "SELECT COALESCE(jsonb_agg(fname), '[]'::jsonb) FROM vsc_cv.source_code WHERE contract_addr=$1 AND is_lockfile=false;",
+      "SELECT fname FROM vsc_cv.source_code WHERE contract_addr=$1 AND is_lockfile=true AND NOT EXISTS (SELECT 1 FROM vsc_cv.contracts c WHERE c.contract_addr=$1 AND c.hidden) LIMIT 1;", // assume only one lockfile per contract
       &[(&addr, Type::VARCHAR)]
     ).await
     .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
-  let lockfilename = ctx.db
+  let (hashes, merkle_root) = bundle_hashes(&ctx, &addr).await?;
+  let result = ContractInfo {
+    address: addr.clone(),
+    code: contract[0].get(0),
+    username: contract[0].get(1),
+    request_ts: contract[0].get::<usize, NaiveDateTime>(2).format("%Y-%m-%dT%H:%M:%S%.6f").to_string(),
+    verified_ts: contract[0].get::<usize, Option<NaiveDateTime>>(3).map(|t| t.format("%Y-%m-%dT%H:%M:%S%.6f").to_string()),
+    status: contract[0].get(4),
+    exports: contract[0].get(5),
+    files,
+    lockfile: lockfilename.first().map(|l| l.get(0)),
+    license: contract[0].get(6),
+    lang: contract[0].get(7),
+    dependencies: contract[0].get(8),
+    hidden: contract[0].get(9),
+    metadata: latest_metadata(&ctx.db, &addr).await?,
+    abi: contract[0].get(10),
+    hashes,
+    merkle_root,
+  };
+  Ok(HttpResponse::Ok().json(result))
+}
+
+#[utoipa::path(
+  tag = "contracts",
+  params(("address" = String, Path, description = "Contract address")),
+  responses((status = 200, description = "Typed ABI of the verified contract", body = Abi), (status = 404, description = "ABI not found", body = ErrorResp))
+)]
+#[get("/contract/{address}/abi")]
+async fn contract_abi(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let addr = path.into_inner();
+  let abi = ctx.db
+    .query("SELECT abi FROM vsc_cv.contracts WHERE contract_addr=$1 AND status=3::SMALLINT;", &[(&addr, Type::VARCHAR)]).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  match abi.first().and_then(|a| a.get::<usize, Option<Value>>(0)) {
+    Some(a) => Ok(HttpResponse::Ok().json(a)),
+    None => Ok(HttpResponse::NotFound().json(ErrorResp::new("abi not found"))),
+  }
+}
+
+#[utoipa::path(
+  tag = "contracts",
+  params(("address" = String, Path, description = "Contract address")),
+  responses((status = 200, description = "Signed verification receipt", body = Receipt), (status = 404, description = "Receipt not found", body = ErrorResp))
+)]
+#[get("/contract/{address}/receipt")]
+async fn contract_receipt(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let addr = path.into_inner();
+  let receipt = ctx.db
+    .query("SELECT statement, signature, key_id FROM vsc_cv.receipts WHERE contract_addr=$1;", &[(&addr, Type::VARCHAR)]).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  if receipt.is_empty() {
+    return Ok(HttpResponse::NotFound().json(ErrorResp::new("receipt not found")));
+  }
+  Ok(
+    HttpResponse::Ok().json(Receipt {
+      statement: receipt[0].get(0),
+      signature: receipt[0].get(1),
+      key_id: receipt[0].get(2),
+      alg: String::from("Ed25519"),
+    })
+  )
+}
+
+#[utoipa::path(
+  tag = "contracts",
+  params(("address" = String, Path, description = "Contract address")),
+  responses((status = 200, description = "Hive attestation broadcast state", body = Attestation), (status = 404, description = "Attestation not found", body = ErrorResp))
+)]
+#[get("/contract/{address}/attestation")]
+async fn contract_attestation(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let addr = path.into_inner();
+  let attestation = ctx.db
     .query(
-      "SELECT fname FROM vsc_cv.source_code WHERE contract_addr=$1 AND is_lockfile=true LIMIT 1;", // assume only one lockfile per contract
+      "SELECT CASE status WHEN 0 THEN 'pending' WHEN 1 THEN 'broadcasted' ELSE 'failed' END, tx_id, attempts, last_error, created_ts, broadcast_ts FROM vsc_cv.attestations WHERE contract_addr=$1;",
       &[(&addr, Type::VARCHAR)]
     ).await
     .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
-  let result =
-    json!({
-    "address": &addr,
-    "code": contract[0].get::<usize, &str>(0),
-    "username": contract[0].get::<usize, &str>(1),
-    "request_ts": &contract[0].get::<usize, NaiveDateTime>(2).format("%Y-%m-%dT%H:%M:%S%.6f").to_string(),
-    "verified_ts": &contract[0].get::<usize, Option<NaiveDateTime>>(3).map(|t| t.format("%Y-%m-%dT%H:%M:%S%.6f").to_string()),
-    "status": contract[0].get::<usize, &str>(4),
-    "exports": contract[0].get::<usize, Option<Value>>(5),
-    "files": files[0].get::<usize, Value>(0),
-    "lockfile": match lockfilename.len() {
-      0 => None,
-      _ => Some(lockfilename[0].get::<usize, &str>(0)),
-    },
-    "license": contract[0].get::<usize, &str>(6),
-    "lang": contract[0].get::<usize, &str>(7),
-    "dependencies": contract[0].get::<usize, Value>(8)
-  });
-  Ok(HttpResponse::Ok().json(result))
+  if attestation.is_empty() {
+    return Ok(HttpResponse::NotFound().json(ErrorResp::new("attestation not found")));
+  }
+  Ok(
+    HttpResponse::Ok().json(Attestation {
+      status: attestation[0].get(0),
+      tx_id: attestation[0].get(1),
+      attempts: attestation[0].get(2),
+      last_error: attestation[0].get(3),
+      created_ts: attestation[0].get::<usize, NaiveDateTime>(4).format("%Y-%m-%dT%H:%M:%S%.6f").to_string(),
+      broadcast_ts: attestation[0].get::<usize, Option<NaiveDateTime>>(5).map(|t| t.format("%Y-%m-%dT%H:%M:%S%.6f").to_string()),
+    })
+  )
 }
 
-#[get("/contract/{address}/files/ls")]
-async fn contract_files_ls(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+#[utoipa::path(
+  tag = "contracts",
+  responses(
+    (status = 200, description = "Public key verifying receipts", body = ReceiptPubKey),
+    (status = 404, description = "Receipts are not enabled", body = ErrorResp)
+  )
+)]
+#[get("/receipts/pubkey")]
+async fn receipt_pubkey() -> Result<HttpResponse, RespErr> {
+  match receipt_key.as_ref() {
+    Some(k) =>
+      Ok(
+        HttpResponse::Ok().json(ReceiptPubKey {
+          alg: String::from("Ed25519"),
+          key_id: k.key_id.clone(),
+          public_key: k.public_key.clone(),
+        })
+      ),
+    None => Ok(HttpResponse::NotFound().json(ErrorResp::new("receipts are not enabled"))),
+  }
+}
+
+#[utoipa::path(
+  tag = "contracts",
+  params(("address" = String, Path, description = "Contract address")),
+  responses((status = 200, description = "Full texts of the licenses of the contract", body = LicenseTexts), (status = 404, description = "Contract not verified", body = ErrorResp))
+)]
+#[get("/contract/{address}/license")]
+async fn contract_license(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let addr = path.into_inner();
+  let contract = ctx.db
+    .query("SELECT license FROM vsc_cv.contracts WHERE contract_addr=$1 AND status=3::SMALLINT;", &[(&addr, Type::VARCHAR)]).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  let expression = match contract.first().and_then(|c| c.get::<usize, Option<String>>(0)) {
+    Some(l) => l,
+    None => {
+      return Ok(HttpResponse::NotFound().json(ErrorResp::new("contract not verified")));
+    }
+  };
+  let custom_texts: HashMap<String, Option<String>> = ctx.db
+    .query("SELECT name, text FROM vsc_cv.licenses;", &[]).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?
+    .iter()
+    .map(|l| (l.get(0), l.get(1)))
+    .collect();
+  let result = license::expression_texts(&expression, &custom_texts).map_err(|msg| RespErr::InternalErr { msg })?;
+  Ok(HttpResponse::Ok().json(result))
+}
+
+/// SHA-256 of every file in the source bundle including the lockfile, and the Merkle root over them.
+async fn bundle_hashes(ctx: &Context, addr: &str) -> Result<(BTreeMap<String, String>, Option<String>), RespErr> {
+  let files: Vec<(String, String)> = ctx.db
+    .query(
+      "SELECT fname, sha256 FROM vsc_cv.source_code WHERE contract_addr=$1 AND sha256 IS NOT NULL AND NOT EXISTS (SELECT 1 FROM vsc_cv.contracts c WHERE c.contract_addr=$1 AND c.hidden);",
+      &[(&addr, Type::VARCHAR)]
+    ).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?
+    .iter()
+    .map(|f| (f.get(0), f.get(1)))
+    .collect();
+  let merkle_root = bundle::merkle_root(&files);
+  Ok((files.into_iter().collect(), merkle_root))
+}
+
+/// Names of the uploaded source files excluding the lockfile.
+async fn source_filenames(ctx: &Context, addr: &str) -> Result<Vec<String>, RespErr> {
   let files = ctx.db
     .query(
-      "SELECT jsonb_agg(fname) FROM vsc_cv.source_code WHERE contract_addr=$1 AND is_lockfile=false;",
+      "SELECT fname FROM vsc_cv.source_code WHERE contract_addr=$1 AND is_lockfile=false AND NOT EXISTS (SELECT 1 FROM vsc_cv.contracts c WHERE c.contract_addr=$1 AND c.hidden);",
       &[(&addr, Type::VARCHAR)]
     ).await
     .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
-  Ok(HttpResponse::Ok().json(files[0].get::<usize, Value>(0)))
+  Ok(
+    files
+      .iter()
+      .map(|f| f.get(0))
+      .collect()
+  )
+}
+
+/// Latest metadata submitted by the verifier of a contract.
+pub async fn latest_metadata(db: &DbPool, addr: &str) -> Result<Option<LatestMetadata>, RespErr> {
+  let metadata = db
+    .query(
+      "SELECT version, ts, metadata FROM vsc_cv.contract_metadata WHERE contract_addr=$1 ORDER BY version DESC LIMIT 1;",
+      &[(&addr, Type::VARCHAR)]
+    ).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  match metadata.first() {
+    Some(m) =>
+      Ok(
+        Some(LatestMetadata {
+          version: m.get(0),
+          ts: m.get::<usize, NaiveDateTime>(1).format("%Y-%m-%dT%H:%M:%S%.6f").to_string(),
+          metadata: serde_json::from_value(m.get(2)).map_err(|e| RespErr::InternalErr { msg: e.to_string() })?,
+        })
+      ),
+    None => Ok(None),
+  }
 }
 
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct FilesLsOpts {
+  /// List the SHA-256 of each file next to its name
+  hashes: Option<bool>,
+}
+
+#[utoipa::path(
+  tag = "contracts",
+  params(("address" = String, Path, description = "Contract address"), FilesLsOpts),
+  responses(
+    (
+      status = 200,
+      description = "Source filenames excluding the lockfile, as objects with their SHA-256 if hashes=true. null if the contract is unknown or has no source files",
+      body = Option<SourceFileList>,
+    )
+  )
+)]
+#[get("/contract/{address}/files/ls")]
+async fn contract_files_ls(path: web::Path<String>, params: web::Query<FilesLsOpts>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let addr = path.into_inner();
+  if !params.hashes.unwrap_or(false) {
+    let files = source_filenames(&ctx, &addr).await?;
+    return Ok(HttpResponse::Ok().json((!files.is_empty()).then_some(SourceFileList::Names(files))));
+  }
+  let files: Vec<SourceFileHash> = ctx.db
+    .query(
+      "SELECT fname, sha256 FROM vsc_cv.source_code WHERE contract_addr=$1 AND is_lockfile=false AND NOT EXISTS (SELECT 1 FROM vsc_cv.contracts c WHERE c.contract_addr=$1 AND c.hidden);",
+      &[(&addr, Type::VARCHAR)]
+    ).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?
+    .iter()
+    .map(|f| SourceFileHash { name: f.get(0), sha256: f.get(1) })
+    .collect();
+  Ok(HttpResponse::Ok().json((!files.is_empty()).then_some(SourceFileList::Hashes(files))))
+}
+
+#[utoipa::path(
+  tag = "contracts",
+  params(("address" = String, Path, description = "Contract address"), ("filename" = String, Path, description = "Source filename")),
+  responses(
+    (status = 200, description = "File contents", body = String, content_type = "text/plain"),
+    (status = 404, description = "File not found", body = String, content_type = "text/plain")
+  )
+)]
 #[get("/contract/{address}/files/cat/{filename}")]
 async fn contract_files_cat(path: web::Path<(String, String)>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let (addr, filename) = path.into_inner();
   let files = ctx.db
     .query(
-      "SELECT content FROM vsc_cv.source_code WHERE contract_addr=$1 AND fname=$2;",
+      "SELECT content FROM vsc_cv.source_code WHERE contract_addr=$1 AND fname=$2 AND NOT EXISTS (SELECT 1 FROM vsc_cv.contracts c WHERE c.contract_addr=$1 AND c.hidden);",
       &[
         (&addr, Type::VARCHAR),
         (&filename, Type::VARCHAR),
@@ -424,21 +828,232 @@ async fn contract_files_cat(path: web::Path<(String, String)>, ctx: web::Data<Co
   Ok(HttpResponse::Ok().body(files[0].get::<usize, String>(0)))
 }
 
+#[utoipa::path(
+  tag = "contracts",
+  params(("address" = String, Path, description = "Contract address")),
+  responses(
+    (
+      status = 200,
+      description = "Contents of all source files excluding the lockfile. null if the contract is unknown or has no source files",
+      body = Option<Vec<SourceFile>>,
+    )
+  )
+)]
 #[get("/contract/{address}/files/catall")]
 async fn contract_files_cat_all(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let addr = path.into_inner();
   let files = ctx.db
     .query(
-      "SELECT jsonb_agg(jsonb_build_object('name',fname,'content',content)) FROM vsc_cv.source_code WHERE contract_addr=$1 AND is_lockfile=false;",
+      "SELECT fname, content FROM vsc_cv.source_code WHERE contract_addr=$1 AND is_lockfile=false AND NOT EXISTS (SELECT 1 FROM vsc_cv.contracts c WHERE c.contract_addr=$1 AND c.hidden);",
       &[(&addr, Type::VARCHAR)]
     ).await
     .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
-  if files.len() == 0 {
-    return Ok(HttpResponse::NotFound().body("Error 404 file not found"));
+  let result: Vec<SourceFile> = files
+    .iter()
+    .map(|f| SourceFile { name: f.get(0), content: f.get(1) })
+    .collect();
+  Ok(HttpResponse::Ok().json((!result.is_empty()).then_some(result)))
+}
+
+#[utoipa::path(
+  tag = "contracts",
+  security(("bearer" = [])),
+  params(("address" = String, Path, description = "Contract address")),
+  request_body = ContractMetadata,
+  responses(
+    (status = 200, description = "New metadata version", body = MetadataUpdated),
+    (status = 400, description = "Invalid metadata or not the verifier of the contract", body = ErrorResp),
+    (status = 401, description = "Unauthorized", body = ErrorResp),
+    (status = 404, description = "Contract not found", body = ErrorResp)
+  )
+)]
+#[post("/contract/{address}/metadata")]
+async fn update_metadata(
+  req: HttpRequest,
+  path: web::Path<String>,
+  req_data: web::Json<ContractMetadata>,
+  ctx: web::Data<Context>
+) -> Result<HttpResponse, RespErr> {
+  let username = auth::verify_auth_token(&req, &ctx.db).await?.user;
+  let address = path.into_inner();
+  let contract = ctx.db
+    .query("SELECT hive_username, status, exports FROM vsc_cv.contracts WHERE contract_addr=$1;", &[(&address, Type::VARCHAR)]).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  if contract.is_empty() {
+    return Ok(HttpResponse::NotFound().json(ErrorResp::new("contract not found")));
+  }
+  if contract[0].get::<usize, i16>(1) != 3 {
+    return Err(RespErr::BadRequest { msg: String::from("Contract is not verified") });
+  }
+  if contract[0].get::<usize, &str>(0) != username {
+    return Err(RespErr::BadRequest { msg: String::from("Only the verifier of the contract may edit its metadata") });
   }
-  Ok(HttpResponse::Ok().json(files[0].get::<usize, Value>(0)))
+  let exports: Vec<String> = contract[0]
+    .get::<usize, Option<Value>>(2)
+    .and_then(|e| serde_json::from_value(e).ok())
+    .unwrap_or_default();
+  let mut metadata = req_data.into_inner();
+  metadata.validate(&exports).map_err(|msg| RespErr::BadRequest { msg })?;
+  let metadata = serde_json::to_value(metadata).map_err(|e| RespErr::InternalErr { msg: e.to_string() })?;
+  let version: i32 = ctx.db
+    .query(
+      "INSERT INTO vsc_cv.contract_metadata(contract_addr,version,hive_username,ts,metadata) SELECT $1,COALESCE(MAX(version),0)+1,$2,$3,$4 FROM vsc_cv.contract_metadata WHERE contract_addr=$1 RETURNING version;",
+      &[
+        (&address, Type::VARCHAR),
+        (&username, Type::VARCHAR),
+        (&Utc::now().naive_utc(), Type::TIMESTAMP),
+        (&metadata, Type::JSONB),
+      ]
+    ).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?
+    [0].get(0);
+  Ok(HttpResponse::Ok().json(MetadataUpdated { success: true, version }))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct MetadataOpts {
+  /// Defaults to the latest version
+  version: Option<i32>,
+}
+
+#[utoipa::path(
+  tag = "contracts",
+  params(("address" = String, Path, description = "Contract address"), MetadataOpts),
+  responses((status = 200, description = "Contract metadata", body = MetadataVersion), (status = 404, description = "Metadata not found", body = ErrorResp))
+)]
+#[get("/contract/{address}/metadata")]
+async fn contract_metadata(
+  path: web::Path<String>,
+  params: web::Query<MetadataOpts>,
+  ctx: web::Data<Context>
+) -> Result<HttpResponse, RespErr> {
+  let addr = path.into_inner();
+  let metadata = ctx.db
+    .query(
+      "SELECT version, hive_username, ts, metadata FROM vsc_cv.contract_metadata WHERE contract_addr=$1 AND ($2::INTEGER IS NULL OR version=$2) ORDER BY version DESC LIMIT 1;",
+      &[
+        (&addr, Type::VARCHAR),
+        (&params.version, Type::INT4),
+      ]
+    ).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  if metadata.is_empty() {
+    return Ok(HttpResponse::NotFound().json(ErrorResp::new("metadata not found")));
+  }
+  Ok(
+    HttpResponse::Ok().json(MetadataVersion {
+      version: metadata[0].get(0),
+      username: metadata[0].get(1),
+      ts: metadata[0].get::<usize, NaiveDateTime>(2).format("%Y-%m-%dT%H:%M:%S%.6f").to_string(),
+      metadata: serde_json::from_value(metadata[0].get(3)).map_err(|e| RespErr::InternalErr { msg: e.to_string() })?,
+    })
+  )
+}
+
+#[utoipa::path(
+  tag = "contracts",
+  params(("address" = String, Path, description = "Contract address")),
+  responses((status = 200, description = "Metadata versions, latest first", body = Vec<MetadataVersionInfo>))
+)]
+#[get("/contract/{address}/metadata/versions")]
+async fn contract_metadata_versions(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let addr = path.into_inner();
+  let versions = ctx.db
+    .query(
+      "SELECT version, hive_username, ts FROM vsc_cv.contract_metadata WHERE contract_addr=$1 ORDER BY version DESC;",
+      &[(&addr, Type::VARCHAR)]
+    ).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  let result: Vec<MetadataVersionInfo> = versions
+    .iter()
+    .map(|v| MetadataVersionInfo {
+      version: v.get(0),
+      username: v.get(1),
+      ts: v.get::<usize, NaiveDateTime>(2).format("%Y-%m-%dT%H:%M:%S%.6f").to_string(),
+    })
+    .collect();
+  Ok(HttpResponse::Ok().json(result))
+}
+
+#[utoipa::path(
+  tag = "verification",
+  params(("address" = String, Path, description = "Contract address")),
+  responses((status = 200, description = "Verification attempts, latest first", body = Vec<VerificationAttempt>))
+)]
+#[get("/contract/{address}/attempts")]
+async fn contract_attempts(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let addr = path.into_inner();
+  let rows = ctx.db
+    .query(
+      "SELECT a.id, a.hive_username, a.request_ts, a.completed_ts, s.name, a.license, lg.name, a.dependencies FROM vsc_cv.verification_attempts a JOIN vsc_cv.status s ON s.id = a.status JOIN vsc_cv.languages lg ON lg.id = a.lang WHERE a.contract_addr=$1 ORDER BY a.id DESC;",
+      &[(&addr, Type::VARCHAR)]
+    ).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  let result: Vec<VerificationAttempt> = rows
+    .iter()
+    .map(|a| VerificationAttempt {
+      id: a.get(0),
+      username: a.get(1),
+      request_ts: a.get::<usize, NaiveDateTime>(2).format("%Y-%m-%dT%H:%M:%S%.6f").to_string(),
+      completed_ts: a.get::<usize, Option<NaiveDateTime>>(3).map(|t| t.format("%Y-%m-%dT%H:%M:%S%.6f").to_string()),
+      status: a.get(4),
+      license: a.get(5),
+      lang: a.get(6),
+      dependencies: a.get(7),
+    })
+    .collect();
+  Ok(HttpResponse::Ok().json(result))
 }
 
+#[utoipa::path(
+  tag = "verification",
+  params(("address" = String, Path, description = "Contract address"), ("id" = i32, Path, description = "Verification attempt id")),
+  responses(
+    (status = 200, description = "Verification attempt with its sources and logs", body = VerificationAttemptDetail),
+    (status = 404, description = "Verification attempt not found", body = ErrorResp)
+  )
+)]
+#[get("/contract/{address}/attempts/{id}")]
+async fn contract_attempt(path: web::Path<(String, i32)>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
+  let (addr, id) = path.into_inner();
+  let attempt = ctx.db
+    .query(
+      "SELECT a.hive_username, a.request_ts, a.completed_ts, s.name, a.license, lg.name, a.dependencies, CASE WHEN c.hidden THEN NULL ELSE a.sources END, a.logs FROM vsc_cv.verification_attempts a JOIN vsc_cv.contracts c ON c.contract_addr = a.contract_addr JOIN vsc_cv.status s ON s.id = a.status JOIN vsc_cv.languages lg ON lg.id = a.lang WHERE a.contract_addr=$1 AND a.id=$2;",
+      &[
+        (&addr, Type::VARCHAR),
+        (&id, Type::INT4),
+      ]
+    ).await
+    .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
+  if attempt.is_empty() {
+    return Ok(HttpResponse::NotFound().json(ErrorResp::new("verification attempt not found")));
+  }
+  Ok(
+    HttpResponse::Ok().json(VerificationAttemptDetail {
+      id,
+      address: addr,
+      username: attempt[0].get(0),
+      request_ts: attempt[0].get::<usize, NaiveDateTime>(1).format("%Y-%m-%dT%H:%M:%S%.6f").to_string(),
+      completed_ts: attempt[0].get::<usize, Option<NaiveDateTime>>(2).map(|t| t.format("%Y-%m-%dT%H:%M:%S%.6f").to_string()),
+      status: attempt[0].get(3),
+      license: attempt[0].get(4),
+      lang: attempt[0].get(5),
+      dependencies: attempt[0].get(6),
+      sources: attempt[0].get::<usize, Option<Value>>(7).unwrap_or(Value::Array(vec![])),
+      logs: attempt[0].get(8),
+    })
+  )
+}
+
+#[utoipa::path(
+  tag = "contracts",
+  params(("cid" = String, Path, description = "Bytecode CID")),
+  responses(
+    (status = 200, description = "Address of a verified contract with the bytecode", body = BytecodeLookup),
+    (status = 404, description = "No matching contracts found", body = ErrorResp)
+  )
+)]
 #[get("/bytecode/{cid}/lookupaddr")]
 async fn bytecode_lookup_addr(path: web::Path<String>, ctx: web::Data<Context>) -> Result<HttpResponse, RespErr> {
   let cid = path.into_inner();
@@ -449,7 +1064,46 @@ async fn bytecode_lookup_addr(path: web::Path<String>, ctx: web::Data<Context>)
     ).await
     .map_err(|e| RespErr::DbErr { msg: e.to_string() })?;
   if addr.len() == 0 {
-    return Ok(HttpResponse::NotFound().json(json!({"error": "no matching contracts found"})));
+    return Ok(HttpResponse::NotFound().json(ErrorResp::new("no matching contracts found")));
   }
-  Ok(HttpResponse::Ok().json(json!({"address": addr[0].get::<usize, &str>(0)})))
+  Ok(HttpResponse::Ok().json(BytecodeLookup { address: addr[0].get(0) }))
 }
+
+#[derive(OpenApi)]
+#[openapi(
+  paths(
+    hello,
+    login_challenge,
+    login,
+    login_refresh,
+    logout,
+    list_sessions,
+    revoke_session,
+    verify_new,
+    upload_file,
+    upload_complete,
+    list_langs,
+    list_licenses,
+    contract_info,
+    contract_abi,
+    contract_receipt,
+    receipt_pubkey,
+    contract_attestation,
+    contract_license,
+    contract_files_ls,
+    contract_files_cat,
+    contract_files_cat_all,
+    update_metadata,
+    contract_metadata,
+    contract_metadata_versions,
+    contract_attempts,
+    contract_attempt,
+    bytecode_lookup_addr
+  )
+)]
+pub struct CvApiDoc;
+
+/// Endpoints served at the root instead of the cv-api scope
+#[derive(OpenApi)]
+#[openapi(paths(jwks))]
+pub struct WellKnownApiDoc;
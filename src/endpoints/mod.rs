@@ -1,2 +1,4 @@
+pub mod admin_api;
 pub mod be_api;
 pub mod cv_api;
+pub mod openapi;
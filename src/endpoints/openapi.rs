@@ -0,0 +1,35 @@
+use actix_web::{ get, HttpResponse, Responder };
+use utoipa::{ openapi::{ self, security::{ HttpAuthScheme, HttpBuilder, SecurityScheme } }, Modify, OpenApi };
+use crate::endpoints::{ admin_api::AdminApiDoc, be_api::BeApiDoc, cv_api::{ CvApiDoc, WellKnownApiDoc } };
+
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+  fn modify(&self, openapi: &mut openapi::OpenApi) {
+    let components = openapi.components.get_or_insert_with(Default::default);
+    components.add_security_scheme(
+      "bearer",
+      SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build())
+    );
+  }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+  info(title = "VSC Blocks Backend", description = "Contract verifier and block explorer API"),
+  paths(openapi_json),
+  nest((path = "/cv-api/v1", api = CvApiDoc), (path = "/cv-api/v1", api = AdminApiDoc), (path = "/be-api/v1", api = BeApiDoc)),
+  modifiers(&SecurityAddon)
+)]
+struct ApiDoc;
+
+/// OpenAPI document of all endpoints.
+pub fn api_doc() -> openapi::OpenApi {
+  ApiDoc::openapi().merge_from(WellKnownApiDoc::openapi())
+}
+
+#[utoipa::path(tag = "openapi", responses((status = 200, description = "OpenAPI document", body = Object)))]
+#[get("/openapi.json")]
+async fn openapi_json() -> impl Responder {
+  HttpResponse::Ok().json(api_doc())
+}
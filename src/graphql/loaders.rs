@@ -0,0 +1,147 @@
+use async_graphql::dataloader::Loader;
+use futures_util::StreamExt;
+use mongodb::{ bson::{ doc, Document }, Collection };
+use serde::{ de::DeserializeOwned, Deserialize };
+use std::collections::HashMap;
+use crate::{ mongo::MongoDB, types::vsc::{ ElectionResultRecord, LedgerBalance, RcUsedAtHeight, WitnessStat, Witnesses } };
+
+async fn collect<T: DeserializeOwned + Send + Sync>(collection: &Collection<Document>, pipeline: Vec<Document>) -> Result<Vec<T>, String> {
+  let mut cursor = collection
+    .aggregate(pipeline)
+    .with_type::<T>().await
+    .map_err(|e| e.to_string())?;
+  let mut results = Vec::new();
+  while let Some(doc) = cursor.next().await {
+    results.push(doc.map_err(|e| e.to_string())?);
+  }
+  Ok(results)
+}
+
+/// Latest document of every key in `keys` by descending `sort_field`.
+fn latest_by(field: &str, keys: &[String], sort_field: &str) -> Vec<Document> {
+  vec![
+    doc! { "$match": { field: { "$in": keys } } },
+    doc! { "$sort": { field: 1, sort_field: -1 } },
+    doc! { "$group": { "_id": format!("${}", field), "doc": { "$first": "$$ROOT" } } },
+    doc! { "$replaceRoot": { "newRoot": "$doc" } },
+    doc! { "$project": { "_id": 0 } }
+  ]
+}
+
+#[derive(Deserialize)]
+struct AccountRc {
+  account: String,
+  block_height: u64,
+  amount: u64,
+}
+
+pub struct EpochLoader {
+  pub db: MongoDB,
+}
+
+impl Loader<i64> for EpochLoader {
+  type Value = ElectionResultRecord;
+  type Error = String;
+
+  async fn load(&self, keys: &[i64]) -> Result<HashMap<i64, Self::Value>, Self::Error> {
+    let mut cursor = self.db.elections.find(doc! { "epoch": { "$in": keys } }).await.map_err(|e| e.to_string())?;
+    let mut results = HashMap::new();
+    while let Some(doc) = cursor.next().await {
+      let epoch = doc.map_err(|e| e.to_string())?;
+      results.insert(epoch.epoch, epoch);
+    }
+    Ok(results)
+  }
+}
+
+/// Latest registration of witnesses by account name.
+pub struct WitnessLoader {
+  pub db: MongoDB,
+}
+
+impl Loader<String> for WitnessLoader {
+  type Value = Witnesses;
+  type Error = String;
+
+  async fn load(&self, keys: &[String]) -> Result<HashMap<String, Self::Value>, Self::Error> {
+    let witnesses: Vec<Witnesses> = collect(&self.db.witnesses.clone_with_type(), latest_by("account", keys, "height")).await?;
+    Ok(
+      witnesses
+        .into_iter()
+        .map(|w| (w.account.clone(), w))
+        .collect()
+    )
+  }
+}
+
+pub struct WitnessStatLoader {
+  pub db: MongoDB,
+}
+
+impl Loader<String> for WitnessStatLoader {
+  type Value = WitnessStat;
+  type Error = String;
+
+  async fn load(&self, keys: &[String]) -> Result<HashMap<String, Self::Value>, Self::Error> {
+    let mut cursor = self.db.witness_stats.find(doc! { "_id": { "$in": keys } }).await.map_err(|e| e.to_string())?;
+    let mut results = HashMap::new();
+    while let Some(doc) = cursor.next().await {
+      let stat = doc.map_err(|e| e.to_string())?;
+      results.insert(stat.proposer.clone(), stat);
+    }
+    Ok(results)
+  }
+}
+
+/// Ledger balances by account prefixed by hive: or did:, including RCs used and pending consensus unstakes.
+/// Every requested account has a value, accounts without a balance record have zero balances.
+pub struct BalanceLoader {
+  pub db: MongoDB,
+}
+
+impl Loader<String> for BalanceLoader {
+  type Value = LedgerBalance;
+  type Error = String;
+
+  async fn load(&self, keys: &[String]) -> Result<HashMap<String, Self::Value>, Self::Error> {
+    let balances: Vec<LedgerBalance> = collect(&self.db.balances.clone_with_type(), latest_by("account", keys, "block_height")).await?;
+    let rcs: Vec<AccountRc> = collect(&self.db.rc.clone_with_type(), latest_by("account", keys, "block_height")).await?;
+    let unstaking: Vec<Document> = collect(&self.db.ledger_actions.clone_with_type(), vec![
+      doc! { "$match": { "to": { "$in": keys }, "status": "pending", "type": "consensus_unstake" } },
+      doc! { "$group": { "_id": "$to", "totalAmount": { "$sum": "$amount" } } }
+    ]).await?;
+    let mut results: HashMap<String, LedgerBalance> = keys
+      .iter()
+      .map(|k| {
+        (k.clone(), LedgerBalance {
+          account: k.clone(),
+          block_height: 0,
+          hbd: 0,
+          hbd_avg: 0,
+          hbd_modify: 0,
+          hbd_savings: 0,
+          hive: 0,
+          hive_consensus: 0,
+          hive_unstaking: Some(0),
+          rc_used: Some(RcUsedAtHeight { block_height: 0, amount: 0 }),
+        })
+      })
+      .collect();
+    for b in balances {
+      if let Some(bal) = results.get_mut(&b.account) {
+        *bal = LedgerBalance { hive_unstaking: Some(0), rc_used: Some(RcUsedAtHeight { block_height: 0, amount: 0 }), ..b };
+      }
+    }
+    for rc in rcs {
+      if let Some(bal) = results.get_mut(&rc.account) {
+        bal.rc_used = Some(RcUsedAtHeight { block_height: rc.block_height, amount: rc.amount });
+      }
+    }
+    for u in unstaking {
+      if let Some(bal) = results.get_mut(u.get_str("_id").unwrap_or_default()) {
+        bal.hive_unstaking = Some(u.get_i64("totalAmount").unwrap_or(0));
+      }
+    }
+    Ok(results)
+  }
+}
@@ -0,0 +1,26 @@
+pub mod loaders;
+pub mod query;
+
+use async_graphql::{ dataloader::DataLoader, EmptyMutation, EmptySubscription, Schema };
+use crate::mongo::MongoDB;
+use loaders::{ BalanceLoader, EpochLoader, WitnessLoader, WitnessStatLoader };
+use query::QueryRoot;
+
+/// Maximum nesting of selections, `blocks { epoch { members { witness { stats { blockCount } } } } }` is 6 levels deep.
+pub const MAX_QUERY_DEPTH: usize = 8;
+/// Maximum complexity of a query where list fields multiply the complexity of their selection by the requested count.
+pub const MAX_QUERY_COMPLEXITY: usize = 10000;
+
+pub type VscSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+pub fn build_schema(vsc_db: &MongoDB) -> VscSchema {
+  Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+    .data(vsc_db.clone())
+    .data(DataLoader::new(EpochLoader { db: vsc_db.clone() }, tokio::spawn))
+    .data(DataLoader::new(WitnessLoader { db: vsc_db.clone() }, tokio::spawn))
+    .data(DataLoader::new(WitnessStatLoader { db: vsc_db.clone() }, tokio::spawn))
+    .data(DataLoader::new(BalanceLoader { db: vsc_db.clone() }, tokio::spawn))
+    .limit_depth(MAX_QUERY_DEPTH)
+    .limit_complexity(MAX_QUERY_COMPLEXITY)
+    .finish()
+}
@@ -0,0 +1,221 @@
+use async_graphql::{ dataloader::DataLoader, ComplexObject, Context, Json, Object, Result };
+use futures_util::StreamExt;
+use mongodb::{ bson::{ doc, Document }, options::FindOptions, Collection };
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use crate::{
+  graphql::loaders::{ BalanceLoader, EpochLoader, WitnessLoader, WitnessStatLoader },
+  mongo::MongoDB,
+  types::vsc::{
+    BlockHeaderRecord,
+    Contract,
+    ElectionMember,
+    ElectionResultRecord,
+    LedgerBalance,
+    LedgerOpLog,
+    TransactionRecord,
+    WitnessStat,
+    Witnesses,
+  },
+};
+
+const MAX_BLOCKS: i64 = 100;
+const MAX_EPOCHS: i64 = 100;
+const MAX_CONTRACTS: i64 = 200;
+
+async fn find_many<T: DeserializeOwned + Send + Sync>(
+  collection: &Collection<T>,
+  filter: Document,
+  sort: Document,
+  count: i64
+) -> Result<Vec<T>> {
+  let opt = FindOptions::builder().sort(sort).limit(count).build();
+  let mut cursor = collection.find(filter).with_options(opt).await?;
+  let mut results = Vec::new();
+  while let Some(doc) = cursor.next().await {
+    results.push(doc?);
+  }
+  Ok(results)
+}
+
+async fn witness_stats(ctx: &Context<'_>, account: &str) -> Result<WitnessStat> {
+  Ok(
+    ctx
+      .data_unchecked::<DataLoader<WitnessStatLoader>>()
+      .load_one(account.to_string()).await?
+      .unwrap_or(WitnessStat {
+        proposer: account.to_string(),
+        block_count: None,
+        election_count: None,
+        last_block: None,
+        last_epoch: None,
+        eligible_blocks: None,
+        signed_blocks: None,
+      })
+  )
+}
+
+pub struct QueryRoot;
+
+#[Object(name = "Query")]
+impl QueryRoot {
+  /// Block by exactly one of its indexed block number, CID or slot height
+  async fn block(&self, ctx: &Context<'_>, id: Option<u32>, cid: Option<String>, slot: Option<u32>) -> Result<Option<BlockHeaderRecord>> {
+    let filter = match (id, cid, slot) {
+      (Some(id), None, None) => doc! { "be_info.block_id": id },
+      (None, Some(cid), None) => doc! { "block": cid },
+      (None, None, Some(slot)) => doc! { "slot_height": slot },
+      _ => {
+        return Err("Exactly one of id, cid or slot must be specified".into());
+      }
+    };
+    Ok(ctx.data_unchecked::<MongoDB>().blocks.find_one(filter).await?)
+  }
+
+  /// Indexed blocks, latest first
+  #[graphql(complexity = "count.unwrap_or(MAX_BLOCKS).clamp(1, MAX_BLOCKS) as usize * child_complexity")]
+  async fn blocks(
+    &self,
+    ctx: &Context<'_>,
+    last_block_id: Option<i64>,
+    count: Option<i64>,
+    proposer: Option<String>,
+    epoch: Option<i64>
+  ) -> Result<Vec<BlockHeaderRecord>> {
+    let mut filter = doc! { "be_info": doc! {"$exists": true} };
+    if let Some(last_block_id) = last_block_id {
+      filter.insert("be_info.block_id", doc! { "$lte": last_block_id });
+    }
+    if let Some(proposer) = proposer {
+      filter.insert("proposer", proposer);
+    }
+    if let Some(epoch) = epoch {
+      filter.insert("be_info.epoch", epoch);
+    }
+    let count = count.unwrap_or(MAX_BLOCKS).clamp(1, MAX_BLOCKS);
+    find_many(&ctx.data_unchecked::<MongoDB>().blocks, filter, doc! { "be_info.block_id": -1 }, count).await
+  }
+
+  async fn epoch(&self, ctx: &Context<'_>, epoch: i64) -> Result<Option<ElectionResultRecord>> {
+    Ok(ctx.data_unchecked::<DataLoader<EpochLoader>>().load_one(epoch).await?)
+  }
+
+  /// Elections, latest first
+  #[graphql(complexity = "count.unwrap_or(MAX_EPOCHS).clamp(1, MAX_EPOCHS) as usize * child_complexity")]
+  async fn epochs(
+    &self,
+    ctx: &Context<'_>,
+    last_epoch: Option<i64>,
+    count: Option<i64>,
+    proposer: Option<String>
+  ) -> Result<Vec<ElectionResultRecord>> {
+    let mut filter = match last_epoch {
+      Some(le) => doc! { "epoch": doc! {"$lte": le} },
+      None => doc! {},
+    };
+    if let Some(proposer) = proposer {
+      filter.insert("proposer", proposer);
+    }
+    let count = count.unwrap_or(MAX_EPOCHS).clamp(1, MAX_EPOCHS);
+    find_many(&ctx.data_unchecked::<MongoDB>().elections, filter, doc! { "epoch": -1 }, count).await
+  }
+
+  /// Latest registration of a witness
+  async fn witness(&self, ctx: &Context<'_>, account: String) -> Result<Option<Witnesses>> {
+    Ok(ctx.data_unchecked::<DataLoader<WitnessLoader>>().load_one(account).await?)
+  }
+
+  /// Latest registration of every witness
+  async fn witnesses(&self, ctx: &Context<'_>) -> Result<Vec<Witnesses>> {
+    let pipeline = vec![
+      doc! { "$sort": { "account": 1, "height": -1 } },
+      doc! { "$group": { "_id": "$account", "doc": { "$first": "$$ROOT" } } },
+      doc! { "$replaceRoot": { "newRoot": "$doc" } },
+      doc! { "$project": { "_id": 0 } }
+    ];
+    let mut cursor = ctx.data_unchecked::<MongoDB>().witnesses.aggregate(pipeline).with_type::<Witnesses>().await?;
+    let mut results = Vec::new();
+    while let Some(doc) = cursor.next().await {
+      results.push(doc?);
+    }
+    Ok(results)
+  }
+
+  async fn contract(&self, ctx: &Context<'_>, id: String) -> Result<Option<Contract>> {
+    Ok(ctx.data_unchecked::<MongoDB>().contracts.find_one(doc! { "id": id }).await?)
+  }
+
+  /// Contracts, latest first
+  #[graphql(complexity = "count.unwrap_or(MAX_CONTRACTS).clamp(1, MAX_CONTRACTS) as usize * child_complexity")]
+  async fn contracts(&self, ctx: &Context<'_>, count: Option<i64>) -> Result<Vec<Contract>> {
+    let count = count.unwrap_or(MAX_CONTRACTS).clamp(1, MAX_CONTRACTS);
+    find_many(&ctx.data_unchecked::<MongoDB>().contracts, doc! {}, doc! { "creation_height": -1 }, count).await
+  }
+
+  /// Ledger balance of an account prefixed by hive: or did:
+  async fn balance(&self, ctx: &Context<'_>, account: String) -> Result<Option<LedgerBalance>> {
+    Ok(ctx.data_unchecked::<DataLoader<BalanceLoader>>().load_one(account).await?)
+  }
+
+  async fn transaction(&self, ctx: &Context<'_>, id: String) -> Result<Option<TransactionRecord>> {
+    Ok(ctx.data_unchecked::<MongoDB>().tx_pool.find_one(doc! { "id": id }).await?)
+  }
+}
+
+#[ComplexObject]
+impl BlockHeaderRecord {
+  /// Election of the epoch the block was produced in
+  async fn epoch(&self, ctx: &Context<'_>) -> Result<Option<ElectionResultRecord>> {
+    match &self.be_info {
+      Some(info) => Ok(ctx.data_unchecked::<DataLoader<EpochLoader>>().load_one(info.epoch as i64).await?),
+      None => Ok(None),
+    }
+  }
+
+  async fn proposer_witness(&self, ctx: &Context<'_>) -> Result<Option<Witnesses>> {
+    Ok(ctx.data_unchecked::<DataLoader<WitnessLoader>>().load_one(self.proposer.clone()).await?)
+  }
+}
+
+#[ComplexObject]
+impl ElectionResultRecord {
+  /// Blocks produced in the epoch, latest first
+  #[graphql(complexity = "count.unwrap_or(MAX_BLOCKS).clamp(1, MAX_BLOCKS) as usize * child_complexity")]
+  async fn blocks(&self, ctx: &Context<'_>, count: Option<i64>) -> Result<Vec<BlockHeaderRecord>> {
+    let count = count.unwrap_or(MAX_BLOCKS).clamp(1, MAX_BLOCKS);
+    find_many(&ctx.data_unchecked::<MongoDB>().blocks, doc! { "be_info.epoch": self.epoch }, doc! { "be_info.block_id": -1 }, count).await
+  }
+
+  async fn proposer_witness(&self, ctx: &Context<'_>) -> Result<Option<Witnesses>> {
+    Ok(ctx.data_unchecked::<DataLoader<WitnessLoader>>().load_one(self.proposer.clone()).await?)
+  }
+}
+
+#[ComplexObject]
+impl ElectionMember {
+  async fn witness(&self, ctx: &Context<'_>) -> Result<Option<Witnesses>> {
+    Ok(ctx.data_unchecked::<DataLoader<WitnessLoader>>().load_one(self.account.clone()).await?)
+  }
+
+  async fn stats(&self, ctx: &Context<'_>) -> Result<WitnessStat> {
+    witness_stats(ctx, &self.account).await
+  }
+}
+
+#[ComplexObject]
+impl Witnesses {
+  async fn stats(&self, ctx: &Context<'_>) -> Result<WitnessStat> {
+    witness_stats(ctx, &self.account).await
+  }
+
+  async fn balance(&self, ctx: &Context<'_>) -> Result<Option<LedgerBalance>> {
+    Ok(ctx.data_unchecked::<DataLoader<BalanceLoader>>().load_one(format!("hive:{}", self.account)).await?)
+  }
+}
+
+#[ComplexObject]
+impl LedgerOpLog {
+  async fn params(&self) -> Option<Json<Value>> {
+    self.params.clone().map(Json)
+  }
+}
@@ -1,7 +1,7 @@
 use futures_util::StreamExt;
 use serde_json::Value;
 use tokio::{ time::{ sleep, Duration }, sync::RwLock };
-use mongodb::{ bson::{ doc, Bson }, options::FindOneOptions, Collection };
+use mongodb::{ bson::{ doc, Bson }, error::Error as MongoErr, options::FindOneOptions, Collection };
 use reqwest;
 use log::{ error, info };
 use std::sync::Arc;
@@ -10,7 +10,7 @@ use crate::{
   config::config,
   types::{
     hive::{ CustomJson, TxByHash },
-    vsc::{ json_to_bson, BlockHeaderRecord, ElectionResultRecord, IndexerState, WitnessStat },
+    vsc::{ json_to_bson, BlockHeaderRecord, ElectionResultRecord, IndexerState, WitnessParticipation, WitnessStat },
   },
 };
 
@@ -21,6 +21,7 @@ pub struct BlockIndexer {
   elections_db: Collection<ElectionResultRecord>,
   indexer2: Collection<IndexerState>,
   witness_stats: Collection<WitnessStat>,
+  witness_participation: Collection<WitnessParticipation>,
   is_running: Arc<RwLock<bool>>,
 }
 
@@ -30,7 +31,8 @@ impl BlockIndexer {
     blocks_db: Collection<BlockHeaderRecord>,
     elections_db: Collection<ElectionResultRecord>,
     indexer2: Collection<IndexerState>,
-    witness_stats: Collection<WitnessStat>
+    witness_stats: Collection<WitnessStat>,
+    witness_participation: Collection<WitnessParticipation>
   ) -> BlockIndexer {
     return BlockIndexer {
       http_client,
@@ -38,6 +40,7 @@ impl BlockIndexer {
       elections_db,
       indexer2,
       witness_stats,
+      witness_participation,
       is_running: Arc::new(RwLock::new(false)),
     };
   }
@@ -48,6 +51,7 @@ impl BlockIndexer {
     let election_db = self.elections_db.clone();
     let indexer2 = self.indexer2.clone();
     let witness_stats = self.witness_stats.clone();
+    let witness_participation = self.witness_participation.clone();
     let running = Arc::clone(&self.is_running);
 
     tokio::spawn(async move {
@@ -65,6 +69,22 @@ impl BlockIndexer {
         Some(state) => (state.l1_height, state.l2_height),
         None => (0, 0),
       };
+      // blocks indexed before signers were recorded have not had their participation counted
+      loop {
+        match backfill_participation(&blocks_db, &election_db, &witness_stats, &witness_participation).await {
+          Ok(0) => {
+            break;
+          }
+          Ok(count) => info!("Counted participation in {} previously indexed blocks", count),
+          Err(e) => {
+            error!("Failed to backfill block participation: {}", e);
+            sleep(Duration::from_secs(120)).await;
+          }
+        }
+        if !*running.read().await {
+          return;
+        }
+      }
       'mainloop: loop {
         let r = running.read().await;
         if !*r {
@@ -132,6 +152,17 @@ impl BlockIndexer {
             break 'mainloop;
           }
           let bv = bv.unwrap();
+          let signers: Vec<&str> = bv
+            .signer_indices()
+            .iter()
+            .filter_map(|i| epoch.members.get(*i).map(|m| m.account.as_str()))
+            .collect();
+          // counted before the block is marked as indexed, recounting a block after a retry is a no-op
+          if let Err(e) = count_participation(&witness_stats, &witness_participation, next_nums.1, &epoch, &bv).await {
+            error!("Failed to count participation in block {}: {}", block.id, e);
+            sleep(Duration::from_secs(120)).await;
+            continue 'mainloop;
+          }
           let up = blocks_db
             .update_one(
               doc! { "block": block.block.clone() },
@@ -142,7 +173,10 @@ impl BlockIndexer {
                     "epoch": epoch.epoch as i32,
                     "signature": json_to_bson(signature),
                     "voted_weight": Bson::from(bv.voted_weight() as i64),
-                    "eligible_weight": Bson::from(bv.eligible_weight() as i64)
+                    "eligible_weight": Bson::from(bv.eligible_weight() as i64),
+                    "signers": signers,
+                    "quorum_met": bv.quorum_met(),
+                    "quorum_margin": bv.quorum_margin()
                   }
                 }
               }
@@ -194,3 +228,102 @@ impl BlockIndexer {
     });
   }
 }
+
+/// Count the block towards the eligible and signed blocks of every epoch member, overall and per epoch.
+///
+/// Each counter document remembers the last block id counted into it in `last_counted_block`, and blocks are
+/// counted in ascending id order, so counting a block again does not change the counters.
+async fn count_participation(
+  witness_stats: &Collection<WitnessStat>,
+  witness_participation: &Collection<WitnessParticipation>,
+  block_id: u32,
+  epoch: &ElectionResultRecord,
+  bv: &BvWeights
+) -> Result<(), MongoErr> {
+  let block_id = block_id as i32;
+  let uncounted = doc! { "$lt": [{ "$ifNull": ["$last_counted_block", -1] }, block_id] };
+  let last_counted = doc! { "$max": [{ "$ifNull": ["$last_counted_block", -1] }, block_id] };
+  for (i, member) in epoch.members.iter().enumerate() {
+    let signed = bv.signed(i) as i32;
+    witness_stats
+      .update_one(
+        doc! { "_id": &member.account },
+        vec![
+          doc! {
+            "$set": {
+              "eligible_blocks": { "$cond": [&uncounted, { "$add": [{ "$ifNull": ["$eligible_blocks", 0] }, 1] }, "$eligible_blocks"] },
+              "signed_blocks": { "$cond": [&uncounted, { "$add": [{ "$ifNull": ["$signed_blocks", 0] }, signed] }, "$signed_blocks"] },
+              "last_counted_block": &last_counted
+            }
+          }
+        ]
+      )
+      .upsert(true).await?;
+    witness_participation
+      .update_one(
+        doc! { "account": &member.account, "epoch": epoch.epoch },
+        vec![
+          doc! {
+            "$set": {
+              "eligible": { "$cond": [&uncounted, { "$add": [{ "$ifNull": ["$eligible", 0] }, 1] }, "$eligible"] },
+              "signed": { "$cond": [&uncounted, { "$add": [{ "$ifNull": ["$signed", 0] }, signed] }, "$signed"] },
+              "last_counted_block": &last_counted
+            }
+          }
+        ]
+      )
+      .upsert(true).await?;
+  }
+  Ok(())
+}
+
+/// Count the participation in up to 100 indexed blocks without signers, oldest first, and record their signers.
+/// Returns the number of blocks processed.
+async fn backfill_participation(
+  blocks_db: &Collection<BlockHeaderRecord>,
+  election_db: &Collection<ElectionResultRecord>,
+  witness_stats: &Collection<WitnessStat>,
+  witness_participation: &Collection<WitnessParticipation>
+) -> Result<usize, MongoErr> {
+  let mut blocks = blocks_db
+    .find(doc! { "be_info": { "$exists": true }, "be_info.signers": { "$exists": false } })
+    .sort(doc! { "be_info.block_id": 1 })
+    .limit(100).await?;
+  let mut count = 0;
+  let mut epoch: Option<ElectionResultRecord> = None;
+  while let Some(block) = blocks.next().await {
+    let block = block?;
+    let info = match block.be_info {
+      Some(info) => info,
+      None => {
+        continue;
+      }
+    };
+    if epoch.as_ref().is_none_or(|e| e.epoch != (info.epoch as i64)) {
+      epoch = election_db.find_one(doc! { "epoch": info.epoch as i64 }).await?;
+    }
+    let epoch = match &epoch {
+      Some(e) => e,
+      None => {
+        error!("Election of epoch {} not found for block {}", info.epoch, block.id);
+        continue;
+      }
+    };
+    let bv = match BvWeights::from_b64url(&info.signature.bv, &epoch.weights) {
+      Ok(bv) => bv,
+      Err(e) => {
+        error!("Failed to decode bv of block {}: {}", block.id, e);
+        continue;
+      }
+    };
+    count_participation(witness_stats, witness_participation, info.block_id, epoch, &bv).await?;
+    let signers: Vec<&str> = bv
+      .signer_indices()
+      .iter()
+      .filter_map(|i| epoch.members.get(*i).map(|m| m.account.as_str()))
+      .collect();
+    blocks_db.update_one(doc! { "block": &block.block }, doc! { "$set": { "be_info.signers": signers } }).await?;
+    count += 1;
+  }
+  Ok(count)
+}
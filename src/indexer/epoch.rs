@@ -99,11 +99,11 @@ impl ElectionIndexer {
           };
           let weights = match sig_obj {
             Some(sign) => {
-              let weights = match election_db.find_one(doc! { "epoch": (next_num as i64)-1 }).await {
+              let (weights, members) = match election_db.find_one(doc! { "epoch": (next_num as i64)-1 }).await {
                 Ok(pe) =>
                   match pe {
-                    Some(pe) => pe.weights,
-                    None => vec![],
+                    Some(pe) => (pe.weights, pe.members),
+                    None => (vec![], vec![]),
                   }
                 Err(e) => {
                   error!("Failed to query previous epoch {}", e);
@@ -112,11 +112,18 @@ impl ElectionIndexer {
                 }
               };
               match BvWeights::from_b64url(&sign.bv, &weights) {
-                Ok(bv) => (bv.voted_weight(), bv.eligible_weight()),
-                Err(_) => (0, 0),
+                Ok(bv) => {
+                  let signers: Vec<String> = bv
+                    .signer_indices()
+                    .iter()
+                    .filter_map(|i| members.get(*i).map(|m| m.account.clone()))
+                    .collect();
+                  (bv.voted_weight(), bv.eligible_weight(), signers, Some((bv.quorum_met(), bv.quorum_margin())))
+                }
+                Err(_) => (0, 0, vec![], None),
               }
             }
-            None => (0, 0),
+            None => (0, 0, vec![], None),
           };
           let up = election_db
             .update_one(
@@ -126,7 +133,10 @@ impl ElectionIndexer {
                     "ts": &tx.timestamp,
                     "signature": json_to_bson(signature),
                     "voted_weight": weights.0 as i64,
-                    "eligible_weight": weights.1 as i64
+                    "eligible_weight": weights.1 as i64,
+                    "signers": weights.2,
+                    "quorum_met": weights.3.map(|q| q.0),
+                    "quorum_margin": weights.3.map(|q| q.1)
                   }
                 }}
             )
@@ -1,7 +1,7 @@
 use mongodb::Collection;
 use crate::{
   indexer::{ blocks::BlockIndexer, epoch::ElectionIndexer },
-  types::vsc::{ BlockHeaderRecord, ElectionResultRecord, IndexerState, WitnessStat },
+  types::vsc::{ BlockHeaderRecord, ElectionResultRecord, IndexerState, WitnessParticipation, WitnessStat },
 };
 
 #[derive(Clone)]
@@ -16,7 +16,8 @@ impl Indexer {
     blocks_db: Collection<BlockHeaderRecord>,
     elections_db: Collection<ElectionResultRecord>,
     indexer2: Collection<IndexerState>,
-    witness_stats: Collection<WitnessStat>
+    witness_stats: Collection<WitnessStat>,
+    witness_participation: Collection<WitnessParticipation>
   ) -> Indexer {
     return Indexer {
       block_idxer: BlockIndexer::init(
@@ -24,7 +25,8 @@ impl Indexer {
         blocks_db.clone(),
         elections_db.clone(),
         indexer2.clone(),
-        witness_stats.clone()
+        witness_stats.clone(),
+        witness_participation
       ),
       election_idxer: ElectionIndexer::init(http_client.clone(), elections_db.clone(), indexer2.clone(), witness_stats.clone()),
     };
@@ -0,0 +1,151 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+use spdx::{ identifiers::{ LICENSES, IS_DEPRECATED }, Expression, LicenseItem };
+use std::collections::HashMap;
+
+/// Maximum length of a license expression stored for a contract
+pub const MAX_EXPRESSION_LEN: usize = 255;
+
+/// All non-deprecated SPDX license identifiers bundled with this build.
+pub fn spdx_ids() -> Vec<&'static str> {
+  LICENSES.iter()
+    .filter(|(_, _, flags)| flags & IS_DEPRECATED == 0)
+    .map(|(id, _, _)| *id)
+    .collect()
+}
+
+/// Validate an SPDX license expression such as `MIT OR Apache-2.0`. Deprecated identifiers are rejected
+/// and `LicenseRef-` identifiers must be one of the custom licenses registered in the database.
+pub fn validate_expression(expression: &str, custom: &[String]) -> Result<String, String> {
+  let expression = expression.trim();
+  if expression.is_empty() || expression.len() > MAX_EXPRESSION_LEN {
+    return Err(format!("License expression must be between 1 and {} characters.", MAX_EXPRESSION_LEN));
+  }
+  let parsed = Expression::parse(expression).map_err(|e| format!("Invalid license expression: {}", e))?;
+  for r in parsed.requirements() {
+    let name = &expression[r.span.start as usize..r.span.end as usize];
+    match &r.req.license {
+      LicenseItem::Spdx { .. } => {
+        if let Some(id) = spdx::license_id(name.trim_end_matches('+')) {
+          if id.is_deprecated() {
+            return Err(format!("License {} is deprecated.", name));
+          }
+        }
+      }
+      LicenseItem::Other { doc_ref, lic_ref } => {
+        if doc_ref.is_some() || !custom.contains(&format!("LicenseRef-{}", lic_ref)) {
+          return Err(format!("License {} is currently unsupported.", name));
+        }
+      }
+    }
+    if let Some(exception) = r.req.exception {
+      if exception.is_deprecated() {
+        return Err(format!("License exception {} is deprecated.", exception.name));
+      }
+    }
+  }
+  Ok(String::from(expression))
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct LicenseText {
+  pub id: String,
+  pub name: String,
+  pub text: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ExceptionText {
+  pub id: String,
+  pub text: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct LicenseTexts {
+  pub expression: String,
+  pub licenses: Vec<LicenseText>,
+  pub exceptions: Vec<ExceptionText>,
+}
+
+/// Full texts of every license and exception referenced by a previously validated expression.
+/// Texts of custom licenses are looked up from the provided map.
+pub fn expression_texts(expression: &str, custom_texts: &HashMap<String, Option<String>>) -> Result<LicenseTexts, String> {
+  let parsed = Expression::parse(expression).map_err(|e| format!("Invalid license expression: {}", e))?;
+  let mut licenses: Vec<LicenseText> = Vec::new();
+  let mut exceptions: Vec<ExceptionText> = Vec::new();
+  for r in parsed.requirements() {
+    let name = expression[r.span.start as usize..r.span.end as usize].trim_end_matches('+');
+    if licenses.iter().any(|l| l.id == name) {
+      continue;
+    }
+    match spdx::license_id(name) {
+      Some(id) =>
+        licenses.push(LicenseText { id: name.to_string(), name: id.full_name.to_string(), text: Some(id.text().to_string()) }),
+      None =>
+        licenses.push(LicenseText {
+          id: name.to_string(),
+          name: name.to_string(),
+          text: custom_texts.get(name).cloned().flatten(),
+        }),
+    }
+    if let Some(exception) = r.req.exception {
+      if !exceptions.iter().any(|e| e.id == exception.name) {
+        exceptions.push(ExceptionText { id: exception.name.to_string(), text: exception.text().to_string() });
+      }
+    }
+  }
+  Ok(LicenseTexts { expression: expression.to_string(), licenses, exceptions })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn custom() -> Vec<String> {
+    vec![String::from("LicenseRef-VSC-1.0")]
+  }
+
+  #[test]
+  fn test_valid_expressions() {
+    assert_eq!(validate_expression("MIT", &[]), Ok(String::from("MIT")));
+    assert_eq!(validate_expression("  MIT OR Apache-2.0 ", &[]), Ok(String::from("MIT OR Apache-2.0")));
+    assert!(validate_expression("(MIT AND BSD-3-Clause) OR GPL-3.0-or-later", &[]).is_ok());
+    assert!(validate_expression("GPL-2.0-or-later WITH Classpath-exception-2.0", &[]).is_ok());
+    assert!(validate_expression("Apache-2.0+", &[]).is_ok());
+  }
+
+  #[test]
+  fn test_length() {
+    assert!(validate_expression("", &[]).is_err());
+    assert!(validate_expression("   ", &[]).is_err());
+    let long = vec!["MIT"; 64].join(" OR ");
+    assert!(long.len() > MAX_EXPRESSION_LEN);
+    assert!(validate_expression(&long, &[]).is_err());
+  }
+
+  #[test]
+  fn test_invalid_syntax() {
+    assert!(validate_expression("MIT OR", &[]).is_err());
+    assert!(validate_expression("MPL 2.0", &[]).is_err());
+    assert!(validate_expression("(MIT", &[]).is_err());
+    assert!(validate_expression("Not-A-License", &[]).is_err());
+  }
+
+  #[test]
+  fn test_deprecated() {
+    assert_eq!(validate_expression("GPL-3.0", &[]), Err(String::from("License GPL-3.0 is deprecated.")));
+    assert!(validate_expression("MIT OR GPL-2.0", &[]).is_err());
+  }
+
+  #[test]
+  fn test_custom_licenses() {
+    assert_eq!(validate_expression("LicenseRef-VSC-1.0", &custom()), Ok(String::from("LicenseRef-VSC-1.0")));
+    assert!(validate_expression("MIT OR LicenseRef-VSC-1.0", &custom()).is_ok());
+    assert_eq!(
+      validate_expression("LicenseRef-Other", &custom()),
+      Err(String::from("License LicenseRef-Other is currently unsupported."))
+    );
+    assert!(validate_expression("LicenseRef-VSC-1.0", &[]).is_err());
+    assert!(validate_expression("DocumentRef-spdx:LicenseRef-VSC-1.0", &custom()).is_err());
+  }
+}
@@ -5,16 +5,26 @@ use reqwest;
 use env_logger;
 use std::process;
 use log::{ error, info };
+mod abi;
+mod attestation;
+mod auth;
+mod authority;
+mod bundle;
 mod config;
 mod constants;
 mod db;
+mod graphql;
 mod mongo;
 mod types;
 mod endpoints;
 mod indexer;
+mod license;
+mod metadata;
+mod receipt;
 mod compiler;
 use types::server::Context;
-use endpoints::{ be_api, cv_api };
+use utoipa_scalar::{ Scalar, Servable };
+use endpoints::{ admin_api, be_api, cv_api, openapi };
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -48,41 +58,84 @@ async fn main() -> std::io::Result<()> {
       process::exit(1);
     }
   };
+  if config.auth.enabled {
+    info!("Loaded {} JWT signing keys", auth::jwt_keys.len());
+  }
+  if let Some(k) = receipt::receipt_key.as_ref() {
+    info!("Signing verification receipts with key {}", k.key_id);
+  }
   let compiler = compiler::Compiler::init(&db_pool);
   compiler.notify();
   let http_client = reqwest::Client::new();
+  if let Some(attestation_conf) = config.attestation.as_ref() {
+    attestation::AttestationPublisher::init(&db_pool, http_client.clone(), attestation_conf).start();
+  }
   if config.be_indexer.unwrap_or(false) {
     let idxer = indexer::indexer::Indexer::init(
       http_client.clone(),
       vsc_db.blocks.clone(),
       vsc_db.elections.clone(),
       vsc_db.indexer2.clone(),
-      vsc_db.witness_stats.clone()
+      vsc_db.witness_stats.clone(),
+      vsc_db.witness_participation.clone()
     );
     idxer.start();
   }
-  let server_ctx = Context { db: db_pool, vsc_db, compiler, http_client: http_client.clone() };
+  let server_ctx = Context {
+    db: db_pool,
+    vsc_db: vsc_db.clone(),
+    compiler,
+    http_client: http_client.clone(),
+    authorities: authority::AuthorityCache::init(http_client.clone()),
+    gql_schema: graphql::build_schema(&vsc_db),
+  };
+  let api_doc = openapi::api_doc();
   HttpServer::new(move || {
     let cors = Cors::default().allow_any_origin().allow_any_method().allow_any_header().max_age(3600);
     App::new()
       .wrap(cors)
       .wrap(NormalizePath::trim())
       .app_data(web::Data::new(server_ctx.clone()))
+      .service(cv_api::jwks)
+      .service(openapi::openapi_json)
+      .service(Scalar::with_url("/docs", api_doc.clone()))
       .service(
         web
           ::scope("/cv-api/v1")
           .service(cv_api::hello)
+          .service(cv_api::login_challenge)
           .service(cv_api::login)
+          .service(cv_api::login_refresh)
+          .service(cv_api::logout)
+          .service(cv_api::list_sessions)
+          .service(cv_api::revoke_session)
           .service(cv_api::verify_new)
           .service(cv_api::upload_file)
           .service(cv_api::upload_complete)
           .service(cv_api::list_langs)
           .service(cv_api::list_licenses)
           .service(cv_api::contract_info)
+          .service(cv_api::contract_abi)
+          .service(cv_api::contract_receipt)
+          .service(cv_api::receipt_pubkey)
+          .service(cv_api::contract_attestation)
+          .service(cv_api::contract_license)
           .service(cv_api::contract_files_ls)
           .service(cv_api::contract_files_cat)
           .service(cv_api::contract_files_cat_all)
+          .service(cv_api::update_metadata)
+          .service(cv_api::contract_metadata)
+          .service(cv_api::contract_metadata_versions)
+          .service(cv_api::contract_attempts)
+          .service(cv_api::contract_attempt)
           .service(cv_api::bytecode_lookup_addr)
+          .service(admin_api::requeue)
+          .service(admin_api::cancel)
+          .service(admin_api::delete_verification)
+          .service(admin_api::hide_contract)
+          .service(admin_api::add_license)
+          .service(admin_api::add_language)
+          .service(admin_api::list_audit)
       )
       .service(
         web
@@ -93,14 +146,24 @@ async fn main() -> std::io::Result<()> {
           .service(be_api::get_witness)
           .service(be_api::get_witness_stats)
           .service(be_api::get_balance)
+          .service(be_api::get_balance_history)
+          .service(be_api::get_account_history)
           .service(be_api::list_epochs)
           .service(be_api::get_epoch)
+          .service(be_api::get_epoch_diff)
           .service(be_api::list_blocks)
           .service(be_api::get_block)
+          .service(be_api::list_quorum)
           .service(be_api::get_tx_output)
+          .service(be_api::list_txs)
           .service(be_api::list_contracts)
           .service(be_api::get_contract)
+          .service(be_api::get_contract_calls)
+          .service(be_api::get_contract_callers)
+          .service(be_api::get_contract_activity)
           .service(be_api::search)
+          .service(be_api::graphql)
+          .service(be_api::graphql_playground)
       )
   })
     .bind((config.server.address.as_str(), config.server.port))?
@@ -0,0 +1,231 @@
+use serde::{ Serialize, Deserialize };
+use utoipa::ToSchema;
+use std::collections::BTreeMap;
+
+pub const MAX_README_LEN: usize = 65536;
+pub const MAX_DESCRIPTION_LEN: usize = 1024;
+pub const MAX_PARAMS: usize = 32;
+pub const MAX_TAGS: usize = 10;
+pub const MAX_TAG_LEN: usize = 32;
+pub const MAX_LINKS: usize = 10;
+pub const MAX_LABEL_LEN: usize = 64;
+pub const MAX_URL_LEN: usize = 512;
+
+/// Documentation of a contract submitted by its verifier, in addition to the name and description from the deploy transaction.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ContractMetadata {
+  /// README in markdown
+  #[serde(default)]
+  pub readme: Option<String>,
+  /// Export name -> documentation, only exports of the verified contract are allowed
+  #[serde(default)]
+  pub exports: BTreeMap<String, ExportDoc>,
+  #[serde(default)]
+  pub tags: Vec<String>,
+  #[serde(default)]
+  pub links: Vec<Link>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ExportDoc {
+  #[serde(default)]
+  pub description: Option<String>,
+  #[serde(default)]
+  pub params: Vec<ParamDoc>,
+  #[serde(default)]
+  pub returns: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ParamDoc {
+  pub name: String,
+  #[serde(default)]
+  pub description: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Link {
+  pub label: String,
+  pub url: String,
+}
+
+fn check_len(field: &str, value: &str, max: usize) -> Result<(), String> {
+  if value.len() > max {
+    return Err(format!("{} must not exceed {} characters.", field, max));
+  }
+  Ok(())
+}
+
+impl ContractMetadata {
+  /// Validate the metadata against the exports of the verified contract. Tags are normalized to lowercase.
+  pub fn validate(&mut self, contract_exports: &[String]) -> Result<(), String> {
+    if let Some(readme) = &self.readme {
+      check_len("README", readme, MAX_README_LEN)?;
+    }
+    for (name, doc) in self.exports.iter() {
+      if !contract_exports.contains(name) {
+        return Err(format!("Contract does not export {}.", name));
+      }
+      if let Some(description) = &doc.description {
+        check_len("Export description", description, MAX_DESCRIPTION_LEN)?;
+      }
+      if let Some(returns) = &doc.returns {
+        check_len("Export return value description", returns, MAX_DESCRIPTION_LEN)?;
+      }
+      if doc.params.len() > MAX_PARAMS {
+        return Err(format!("Export {} must not document more than {} parameters.", name, MAX_PARAMS));
+      }
+      for p in doc.params.iter() {
+        if p.name.is_empty() {
+          return Err(format!("Parameter names of export {} must not be empty.", name));
+        }
+        check_len("Parameter name", &p.name, MAX_LABEL_LEN)?;
+        if let Some(description) = &p.description {
+          check_len("Parameter description", description, MAX_DESCRIPTION_LEN)?;
+        }
+      }
+    }
+    if self.tags.len() > MAX_TAGS {
+      return Err(format!("There must not be more than {} tags.", MAX_TAGS));
+    }
+    for tag in self.tags.iter_mut() {
+      *tag = tag.trim().to_ascii_lowercase();
+      if tag.is_empty() || tag.len() > MAX_TAG_LEN || !tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Tags must be between 1 and {} characters containing only a-z, 0-9 and -.", MAX_TAG_LEN));
+      }
+    }
+    self.tags.sort();
+    self.tags.dedup();
+    if self.links.len() > MAX_LINKS {
+      return Err(format!("There must not be more than {} links.", MAX_LINKS));
+    }
+    for link in self.links.iter() {
+      if link.label.is_empty() {
+        return Err(String::from("Link labels must not be empty."));
+      }
+      check_len("Link label", &link.label, MAX_LABEL_LEN)?;
+      check_len("Link URL", &link.url, MAX_URL_LEN)?;
+      if !link.url.starts_with("https://") && !link.url.starts_with("http://") {
+        return Err(format!("Link {} must be an http(s) URL.", link.label));
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn exports() -> Vec<String> {
+    vec![String::from("transfer"), String::from("mint")]
+  }
+
+  fn valid() -> ContractMetadata {
+    let mut exports = BTreeMap::new();
+    exports.insert(String::from("transfer"), ExportDoc {
+      description: Some(String::from("Transfer tokens")),
+      params: vec![ParamDoc { name: String::from("to"), description: Some(String::from("Recipient")) }],
+      returns: Some(String::from("Nothing")),
+    });
+    ContractMetadata {
+      readme: Some(String::from("# Token")),
+      exports,
+      tags: vec![String::from(" Token "), String::from("nft"), String::from("token")],
+      links: vec![Link { label: String::from("Website"), url: String::from("https://vsc.eco") }],
+    }
+  }
+
+  #[test]
+  fn test_valid() {
+    let mut m = valid();
+    assert_eq!(m.validate(&exports()), Ok(()));
+    assert_eq!(m.tags, vec![String::from("nft"), String::from("token")]);
+    assert_eq!(ContractMetadata::default().validate(&[]), Ok(()));
+  }
+
+  #[test]
+  fn test_lengths() {
+    let mut m = valid();
+    m.readme = Some("a".repeat(MAX_README_LEN + 1));
+    assert!(m.validate(&exports()).is_err());
+
+    let mut m = valid();
+    m.exports.get_mut("transfer").unwrap().description = Some("a".repeat(MAX_DESCRIPTION_LEN + 1));
+    assert!(m.validate(&exports()).is_err());
+
+    let mut m = valid();
+    m.exports.get_mut("transfer").unwrap().returns = Some("a".repeat(MAX_DESCRIPTION_LEN + 1));
+    assert!(m.validate(&exports()).is_err());
+
+    let mut m = valid();
+    m.exports.get_mut("transfer").unwrap().params = (0..=MAX_PARAMS).map(|i| ParamDoc { name: format!("p{}", i), description: None }).collect();
+    assert!(m.validate(&exports()).is_err());
+
+    let mut m = valid();
+    m.exports.get_mut("transfer").unwrap().params[0].name = "a".repeat(MAX_LABEL_LEN + 1);
+    assert!(m.validate(&exports()).is_err());
+
+    let mut m = valid();
+    m.tags = (0..=MAX_TAGS).map(|i| format!("tag{}", i)).collect();
+    assert!(m.validate(&exports()).is_err());
+
+    let mut m = valid();
+    m.tags = vec!["a".repeat(MAX_TAG_LEN + 1)];
+    assert!(m.validate(&exports()).is_err());
+
+    let mut m = valid();
+    m.links = (0..=MAX_LINKS).map(|i| Link { label: format!("l{}", i), url: String::from("https://vsc.eco") }).collect();
+    assert!(m.validate(&exports()).is_err());
+
+    let mut m = valid();
+    m.links[0].label = "a".repeat(MAX_LABEL_LEN + 1);
+    assert!(m.validate(&exports()).is_err());
+
+    let mut m = valid();
+    m.links[0].url = format!("https://{}", "a".repeat(MAX_URL_LEN));
+    assert!(m.validate(&exports()).is_err());
+  }
+
+  #[test]
+  fn test_urls() {
+    let mut m = valid();
+    m.links[0].url = String::from("http://vsc.eco");
+    assert_eq!(m.validate(&exports()), Ok(()));
+
+    for url in ["javascript:alert(1)", "ftp://vsc.eco", "vsc.eco", ""] {
+      let mut m = valid();
+      m.links[0].url = String::from(url);
+      assert_eq!(m.validate(&exports()), Err(String::from("Link Website must be an http(s) URL.")));
+    }
+  }
+
+  #[test]
+  fn test_fields() {
+    let mut m = valid();
+    m.exports.insert(String::from("burn"), ExportDoc::default());
+    assert_eq!(m.validate(&exports()), Err(String::from("Contract does not export burn.")));
+
+    let mut m = valid();
+    m.exports.get_mut("transfer").unwrap().params[0].name = String::new();
+    assert!(m.validate(&exports()).is_err());
+
+    for tag in ["", "  ", "white space", "emoji✓", "under_score"] {
+      let mut m = valid();
+      m.tags = vec![String::from(tag)];
+      assert!(m.validate(&exports()).is_err(), "tag {:?} accepted", tag);
+    }
+
+    let mut m = valid();
+    m.links[0].label = String::new();
+    assert_eq!(m.validate(&exports()), Err(String::from("Link labels must not be empty.")));
+
+    assert!(serde_json::from_str::<ContractMetadata>(r#"{"readme":"a","author":"b"}"#).is_err());
+    assert!(serde_json::from_str::<ContractMetadata>(r#"{"links":[{"label":"a","url":"https://a","icon":"b"}]}"#).is_err());
+    assert!(serde_json::from_str::<ContractMetadata>(r#"{"exports":{"mint":{"params":[{"description":"a"}]}}}"#).is_err());
+  }
+}
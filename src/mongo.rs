@@ -1,6 +1,6 @@
-use mongodb::{ options::ClientOptions, Client, Collection };
+use mongodb::{ bson::doc, options::ClientOptions, Client, Collection, IndexModel };
 use std::error::Error;
-use log::info;
+use log::{ error, info };
 use crate::types::vsc::{
   BlockHeaderRecord,
   Contract,
@@ -11,6 +11,7 @@ use crate::types::vsc::{
   LedgerBalance,
   RcUsedAtHeight,
   TransactionRecord,
+  WitnessParticipation,
   WitnessStat,
   Witnesses,
 };
@@ -28,6 +29,7 @@ pub struct MongoDB {
   pub rc: Collection<RcUsedAtHeight>,
   pub indexer2: Collection<IndexerState>,
   pub witness_stats: Collection<WitnessStat>,
+  pub witness_participation: Collection<WitnessParticipation>,
 }
 
 impl MongoDB {
@@ -37,7 +39,7 @@ impl MongoDB {
     let db = client.database("go-vsc");
     let db2 = client.database("vsc2");
     info!("Connected to VSC MongoDB database successfully");
-    Ok(MongoDB {
+    let mongo = MongoDB {
       contracts: db.collection("contracts"),
       elections: db.collection("elections"),
       witnesses: db.collection("witnesses"),
@@ -49,6 +51,21 @@ impl MongoDB {
       rc: db.collection("rcs"),
       indexer2: db2.collection("indexer_state"),
       witness_stats: db2.collection("witness_stats"),
-    })
+      witness_participation: db2.collection("witness_participation"),
+    };
+    mongo.setup_indexes().await;
+    Ok(mongo)
+  }
+
+  /// Indexes backing the paginated transaction listings. Listings still work without them, only slower,
+  /// so failing to create them (e.g. without write access to go-vsc) is not fatal.
+  async fn setup_indexes(&self) {
+    let tx_indexes = vec![
+      IndexModel::builder().keys(doc! { "anchr_height": -1, "id": -1 }).build(),
+      IndexModel::builder().keys(doc! { "data.contract_id": 1, "anchr_height": -1, "id": -1 }).build(),
+    ];
+    if let Err(e) = self.tx_pool.create_indexes(tx_indexes).await {
+      error!("Failed to create transaction_pool indexes: {}", e);
+    }
   }
 }
@@ -0,0 +1,123 @@
+use serde::{ Serialize, Deserialize };
+use sha2::{ Sha256, Digest };
+use ring::signature::{ Ed25519KeyPair, KeyPair };
+use lazy_static::lazy_static;
+use crate::config::config;
+
+pub const RECEIPT_VERSION: u8 = 1;
+
+pub struct ReceiptKey {
+  /// First 8 bytes of the SHA-256 of the public key in hex
+  pub key_id: String,
+  pub public_key: String,
+  pair: Ed25519KeyPair,
+}
+
+lazy_static! {
+  /// Ed25519 key signing verification receipts, receipts are not issued when not configured
+  pub static ref receipt_key: Option<ReceiptKey> = config.receipts.as_ref().map(|r| {
+    let der = hex::decode(&r.private_key).expect("Receipt signing key must be hex encoded");
+    ReceiptKey::from_pkcs8(&der).expect("Invalid receipt signing key")
+  });
+}
+
+impl ReceiptKey {
+  pub fn from_pkcs8(der: &[u8]) -> Result<Self, String> {
+    let pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(der).map_err(|e| e.to_string())?;
+    Ok(ReceiptKey {
+      key_id: hex::encode(&Sha256::digest(pair.public_key().as_ref())[..8]),
+      public_key: hex::encode(pair.public_key().as_ref()),
+      pair,
+    })
+  }
+}
+
+/// Statement attesting a successful verification. The signature covers the exact bytes of its JSON serialization.
+#[derive(Serialize, Deserialize)]
+pub struct ReceiptStatement {
+  pub version: u8,
+  pub contract_addr: String,
+  pub bytecode_cid: String,
+  pub source_merkle_root: Option<String>,
+  pub compiler_image: String,
+  pub compiler_image_digest: String,
+  pub verified_ts: String,
+}
+
+pub struct SignedReceipt {
+  pub statement: String,
+  pub signature: String,
+  pub key_id: String,
+}
+
+impl ReceiptStatement {
+  pub fn sign(&self) -> Option<SignedReceipt> {
+    self.sign_with(receipt_key.as_ref()?)
+  }
+
+  fn sign_with(&self, key: &ReceiptKey) -> Option<SignedReceipt> {
+    let statement = serde_json::to_string(self).ok()?;
+    Some(SignedReceipt {
+      signature: hex::encode(key.pair.sign(statement.as_bytes()).as_ref()),
+      statement,
+      key_id: key.key_id.clone(),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ring::{ rand::SystemRandom, signature::{ UnparsedPublicKey, ED25519 } };
+
+  fn key() -> ReceiptKey {
+    let der = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+    ReceiptKey::from_pkcs8(der.as_ref()).unwrap()
+  }
+
+  fn statement() -> ReceiptStatement {
+    ReceiptStatement {
+      version: RECEIPT_VERSION,
+      contract_addr: String::from("vs41q9c3yg1"),
+      bytecode_cid: String::from("bafkreigh2akiscaildcqabsyg3dfr6chu3fgpregiymsck7e7aqa4s52zy"),
+      source_merkle_root: Some(String::from("8f434346648f6b96df89dda901c5176b10a6d83961dd3c1ac88b59b2dc327aa4")),
+      compiler_image: String::from("vsc-as-compiler"),
+      compiler_image_digest: String::from("vsc-as-compiler@sha256:4a5bd5b1ae4e5e2d4d4b4c2e7b3f2c1a0e8d9f6a7b5c3d1e2f0a9b8c7d6e5f4a"),
+      verified_ts: String::from("2026-10-18T12:00:00.000000"),
+    }
+  }
+
+  fn verify(public_key: &str, receipt: &SignedReceipt) -> bool {
+    let public_key = hex::decode(public_key).unwrap();
+    let signature = hex::decode(&receipt.signature).unwrap();
+    UnparsedPublicKey::new(&ED25519, public_key).verify(receipt.statement.as_bytes(), &signature).is_ok()
+  }
+
+  #[test]
+  fn test_sign_verify() {
+    let key = key();
+    let receipt = statement().sign_with(&key).unwrap();
+    assert_eq!(receipt.key_id, key.key_id);
+    assert_eq!(receipt.key_id.len(), 16);
+    assert!(verify(&key.public_key, &receipt));
+
+    let parsed: ReceiptStatement = serde_json::from_str(&receipt.statement).unwrap();
+    assert_eq!(parsed.contract_addr, "vs41q9c3yg1");
+    assert_eq!(parsed.compiler_image_digest, statement().compiler_image_digest);
+    assert_eq!(serde_json::to_string(&parsed).unwrap(), receipt.statement);
+  }
+
+  #[test]
+  fn test_verify_rejects_tampering() {
+    let key = key();
+    let receipt = statement().sign_with(&key).unwrap();
+
+    let tampered = SignedReceipt {
+      statement: receipt.statement.replace("vs41q9c3yg1", "vs41q9c3yg2"),
+      signature: receipt.signature.clone(),
+      key_id: receipt.key_id.clone(),
+    };
+    assert!(!verify(&key.public_key, &tampered));
+    assert!(!verify(&self::key().public_key, &receipt));
+  }
+}
@@ -2,22 +2,21 @@
 -- Exceptions raised here are considered assertion errors that should not happen in production.
 
 -- New contract verification
+-- License expressions are validated against the bundled SPDX license list on the API
+DROP FUNCTION IF EXISTS vsc_cv.can_verify_new(VARCHAR, VARCHAR, VARCHAR);
 CREATE OR REPLACE FUNCTION vsc_cv.can_verify_new(
   _contract_addr VARCHAR,
-  _license VARCHAR,
   _lang VARCHAR
 )
 RETURNS TEXT AS $$
 DECLARE
   _status SMALLINT;
 BEGIN
-  IF (SELECT NOT EXISTS (SELECT 1 FROM vsc_cv.licenses l WHERE l.name = _license)) THEN
-    RETURN format('License %s is currently unsupported.', _license);
-  ELSIF (SELECT NOT EXISTS (SELECT 1 FROM vsc_cv.languages l WHERE l.name = _lang)) THEN
+  IF (SELECT NOT EXISTS (SELECT 1 FROM vsc_cv.languages l WHERE l.name = _lang)) THEN
     RETURN format('Language %s is currently unsupported.', _lang);
   END IF;
   SELECT status INTO _status FROM vsc_cv.contracts c WHERE c.contract_addr = _contract_addr;
-  IF _status <> 0 AND _status <> 4 AND _status <> 5 THEN
+  IF _status <> 0 AND _status <> 4 AND _status <> 5 AND _status <> 6 THEN
     RETURN 'Contract is already verified or being verified.';
   ELSE
     RETURN '';
@@ -36,20 +35,42 @@ CREATE OR REPLACE FUNCTION vsc_cv.verify_new(
 )
 RETURNS void AS $$
 DECLARE
-  _license_id SMALLINT;
   _lang_id SMALLINT;
 BEGIN
-  SELECT id INTO _license_id FROM vsc_cv.licenses WHERE name=_license;
   SELECT id INTO _lang_id FROM vsc_cv.languages WHERE name=_lang;
   INSERT INTO vsc_cv.contracts(contract_addr,bytecode_cid,hive_username,request_ts,status,license,lang,dependencies)
-    VALUES(_addr,_bytecode,_user,_ts,0::SMALLINT,_license_id,_lang_id,_deps)
+    VALUES(_addr,_bytecode,_user,_ts,0::SMALLINT,_license,_lang_id,_deps)
     ON CONFLICT(contract_addr) DO UPDATE SET
       hive_username = _user,
       request_ts = _ts,
       status = 0::SMALLINT,
-      license = _license_id,
+      license = _license,
       lang = _lang_id,
       dependencies = _deps;
+  INSERT INTO vsc_cv.verification_attempts(contract_addr,hive_username,request_ts,status,license,lang,dependencies)
+    VALUES(_addr,_user,_ts,0::SMALLINT,_license,_lang_id,_deps);
+END $$
+LANGUAGE plpgsql VOLATILE;
+
+-- Verification attempt history
+CREATE OR REPLACE FUNCTION vsc_cv.update_attempt(
+  _addr VARCHAR,
+  _status SMALLINT,
+  _logs VARCHAR,
+  _ts TIMESTAMP
+)
+RETURNS void AS $$
+BEGIN
+  -- sources are snapshotted when queued and again on completion to include the generated lockfile
+  UPDATE vsc_cv.verification_attempts SET
+    status = _status,
+    logs = COALESCE(_logs, logs),
+    completed_ts = _ts,
+    sources = CASE WHEN _status = 1 OR _status = 3 THEN (
+      SELECT jsonb_agg(jsonb_build_object('name',fname,'is_lockfile',is_lockfile,'content',content) ORDER BY fname)
+      FROM vsc_cv.source_code WHERE contract_addr = _addr
+    ) ELSE sources END
+  WHERE id = (SELECT MAX(id) FROM vsc_cv.verification_attempts WHERE contract_addr = _addr);
 END $$
 LANGUAGE plpgsql VOLATILE;
 
@@ -77,4 +98,77 @@ BEGIN
     RETURN '';
   END IF;
 END $$
-LANGUAGE plpgsql VOLATILE;
\ No newline at end of file
+LANGUAGE plpgsql VOLATILE;
+
+-- Admin removal of a contract verification including its history
+CREATE OR REPLACE FUNCTION vsc_cv.delete_verification(
+  _addr VARCHAR
+)
+RETURNS void AS $$
+BEGIN
+  DELETE FROM vsc_cv.source_code WHERE contract_addr = _addr;
+  DELETE FROM vsc_cv.verification_attempts WHERE contract_addr = _addr;
+  DELETE FROM vsc_cv.contract_metadata WHERE contract_addr = _addr;
+  DELETE FROM vsc_cv.receipts WHERE contract_addr = _addr;
+  DELETE FROM vsc_cv.attestations WHERE contract_addr = _addr;
+  DELETE FROM vsc_cv.contracts WHERE contract_addr = _addr;
+END $$
+LANGUAGE plpgsql VOLATILE;
+-- Admin status change of a verification together with its audit log entry
+-- The status is only changed from one of _from, returns the previous status or NULL if the contract does not exist
+-- Requeuing starts a new attempt so that the completed one is kept, other changes close the attempt if it is still open
+CREATE OR REPLACE FUNCTION vsc_cv.admin_set_status(
+  _addr VARCHAR,
+  _from SMALLINT[],
+  _status SMALLINT,
+  _completed_ts TIMESTAMP,
+  _user VARCHAR,
+  _action VARCHAR,
+  _ts TIMESTAMP
+)
+RETURNS SMALLINT AS $$
+DECLARE
+  _prev SMALLINT;
+BEGIN
+  SELECT status INTO _prev FROM vsc_cv.contracts WHERE contract_addr = _addr FOR UPDATE;
+  IF _prev IS NULL OR NOT (_prev = ANY(_from)) THEN
+    RETURN _prev;
+  END IF;
+  UPDATE vsc_cv.contracts SET status = _status WHERE contract_addr = _addr;
+  IF _status = 1 THEN
+    INSERT INTO vsc_cv.verification_attempts(contract_addr,hive_username,request_ts,status,license,lang,dependencies,sources)
+      SELECT c.contract_addr, c.hive_username, _ts, _status, c.license, c.lang, c.dependencies, (
+        SELECT jsonb_agg(jsonb_build_object('name',fname,'is_lockfile',is_lockfile,'content',content) ORDER BY fname)
+        FROM vsc_cv.source_code WHERE contract_addr = _addr
+      ) FROM vsc_cv.contracts c WHERE c.contract_addr = _addr;
+  ELSE
+    UPDATE vsc_cv.verification_attempts SET status = _status, completed_ts = _completed_ts
+      WHERE id = (SELECT MAX(id) FROM vsc_cv.verification_attempts WHERE contract_addr = _addr) AND completed_ts IS NULL;
+  END IF;
+  INSERT INTO vsc_cv.admin_audit(username,action,target,details,ts)
+    VALUES(_user,_action,_addr,jsonb_build_object('prev_status',_prev),_ts);
+  RETURN _prev;
+END $$
+LANGUAGE plpgsql VOLATILE;
+
+-- Admin removal of a verification that is not in progress together with its audit log entry
+-- Returns the previous status or NULL if the contract does not exist
+CREATE OR REPLACE FUNCTION vsc_cv.admin_delete_verification(
+  _addr VARCHAR,
+  _user VARCHAR,
+  _ts TIMESTAMP
+)
+RETURNS SMALLINT AS $$
+DECLARE
+  _prev SMALLINT;
+BEGIN
+  SELECT status INTO _prev FROM vsc_cv.contracts WHERE contract_addr = _addr FOR UPDATE;
+  IF _prev IS NULL OR _prev = 2 THEN
+    RETURN _prev;
+  END IF;
+  PERFORM vsc_cv.delete_verification(_addr);
+  INSERT INTO vsc_cv.admin_audit(username,action,target,details,ts)
+    VALUES(_user,'delete',_addr,jsonb_build_object('prev_status',_prev),_ts);
+  RETURN _prev;
+END $$
+LANGUAGE plpgsql VOLATILE;
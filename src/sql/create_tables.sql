@@ -5,9 +5,11 @@ CREATE TABLE vsc_cv.languages(
   name VARCHAR(20) UNIQUE
 );
 
+-- Custom LicenseRef- identifiers, SPDX licenses are bundled with the server
 CREATE TABLE vsc_cv.licenses(
   id SMALLINT PRIMARY KEY,
-  name VARCHAR(20) UNIQUE
+  name VARCHAR(64) UNIQUE,
+  text VARCHAR
 );
 
 CREATE TABLE vsc_cv.status(
@@ -18,14 +20,16 @@ CREATE TABLE vsc_cv.status(
 CREATE TABLE vsc_cv.contracts(
   contract_addr VARCHAR(68) PRIMARY KEY,
   bytecode_cid VARCHAR(59) NOT NULL,
-  hive_username VARCHAR(16) NOT NULL,
+  hive_username VARCHAR(68) NOT NULL,
   request_ts TIMESTAMP NOT NULL,
   verified_ts TIMESTAMP,
   status SMALLINT NOT NULL REFERENCES vsc_cv.status(id),
   exports jsonb,
-  license SMALLINT REFERENCES vsc_cv.licenses(id),
+  abi jsonb,
+  license VARCHAR(255),
   lang SMALLINT NOT NULL REFERENCES vsc_cv.languages(id),
-  dependencies jsonb
+  dependencies jsonb,
+  hidden BOOLEAN NOT NULL DEFAULT FALSE
 );
 
 CREATE TABLE vsc_cv.source_code(
@@ -33,30 +37,87 @@ CREATE TABLE vsc_cv.source_code(
   fname VARCHAR(50) NOT NULL,
   is_lockfile BOOLEAN NOT NULL DEFAULT FALSE,
   content VARCHAR,
+  sha256 VARCHAR(64),
   PRIMARY KEY(contract_addr, fname)
 );
 
+CREATE TABLE vsc_cv.verification_attempts(
+  id SERIAL PRIMARY KEY,
+  contract_addr VARCHAR(68) NOT NULL REFERENCES vsc_cv.contracts(contract_addr),
+  hive_username VARCHAR(68) NOT NULL,
+  request_ts TIMESTAMP NOT NULL,
+  completed_ts TIMESTAMP,
+  status SMALLINT NOT NULL REFERENCES vsc_cv.status(id),
+  license VARCHAR(255),
+  lang SMALLINT NOT NULL REFERENCES vsc_cv.languages(id),
+  dependencies jsonb,
+  sources jsonb,
+  logs VARCHAR
+);
+CREATE INDEX verification_attempts_contract_addr_idx ON vsc_cv.verification_attempts(contract_addr);
+
+CREATE TABLE vsc_cv.contract_metadata(
+  contract_addr VARCHAR(68) NOT NULL REFERENCES vsc_cv.contracts(contract_addr),
+  version INTEGER NOT NULL,
+  hive_username VARCHAR(68) NOT NULL,
+  ts TIMESTAMP NOT NULL,
+  metadata jsonb NOT NULL,
+  PRIMARY KEY(contract_addr, version)
+);
+
+CREATE TABLE vsc_cv.receipts(
+  contract_addr VARCHAR(68) PRIMARY KEY REFERENCES vsc_cv.contracts(contract_addr),
+  statement VARCHAR NOT NULL,
+  signature VARCHAR(128) NOT NULL,
+  key_id VARCHAR(16) NOT NULL
+);
+
+-- status 0: pending, 1: broadcasted, 2: failed
+CREATE TABLE vsc_cv.attestations(
+  contract_addr VARCHAR(68) PRIMARY KEY REFERENCES vsc_cv.contracts(contract_addr),
+  status SMALLINT NOT NULL DEFAULT 0,
+  tx_id VARCHAR(40),
+  attempts SMALLINT NOT NULL DEFAULT 0,
+  last_error VARCHAR,
+  created_ts TIMESTAMP NOT NULL,
+  broadcast_ts TIMESTAMP
+);
+
+CREATE TABLE vsc_cv.login_challenges(
+  nonce VARCHAR(32) PRIMARY KEY,
+  account VARCHAR(68) NOT NULL,
+  expiry TIMESTAMP NOT NULL
+);
+CREATE INDEX login_challenges_account_idx ON vsc_cv.login_challenges(account);
+
+CREATE TABLE vsc_cv.sessions(
+  id VARCHAR(32) PRIMARY KEY,
+  username VARCHAR(68) NOT NULL,
+  network VARCHAR(10) NOT NULL,
+  refresh_hash VARCHAR(64) NOT NULL,
+  created_ts TIMESTAMP NOT NULL,
+  last_refresh_ts TIMESTAMP NOT NULL,
+  expiry TIMESTAMP NOT NULL,
+  revoked BOOLEAN NOT NULL DEFAULT FALSE
+);
+CREATE INDEX sessions_username_idx ON vsc_cv.sessions(username);
+
+CREATE TABLE vsc_cv.admin_audit(
+  id SERIAL PRIMARY KEY,
+  username VARCHAR(68) NOT NULL,
+  action VARCHAR(30) NOT NULL,
+  target VARCHAR(68),
+  details jsonb,
+  ts TIMESTAMP NOT NULL
+);
+
 INSERT INTO vsc_cv.status(id, name) VALUES (0, 'pending');
 INSERT INTO vsc_cv.status(id, name) VALUES (1, 'queued');
 INSERT INTO vsc_cv.status(id, name) VALUES (2, 'in progress');
 INSERT INTO vsc_cv.status(id, name) VALUES (3, 'success');
 INSERT INTO vsc_cv.status(id, name) VALUES (4, 'failed');
 INSERT INTO vsc_cv.status(id, name) VALUES (5, 'not match');
-
--- Names must follow SPDX identifier listed in https://spdx.org/licenses
--- Full text may be found in https://github.com/spdx/license-list-data/tree/main/text
-INSERT INTO vsc_cv.licenses(id, name) VALUES (0, 'MIT');
-INSERT INTO vsc_cv.licenses(id, name) VALUES (1, 'Apache-2.0');
-INSERT INTO vsc_cv.licenses(id, name) VALUES (2, 'GPL-3.0-only');
-INSERT INTO vsc_cv.licenses(id, name) VALUES (3, 'GPL-3.0-or-later');
-INSERT INTO vsc_cv.licenses(id, name) VALUES (4, 'LGPL-3.0-only');
-INSERT INTO vsc_cv.licenses(id, name) VALUES (5, 'LGPL-3.0-or-later');
-INSERT INTO vsc_cv.licenses(id, name) VALUES (6, 'AGPL-3.0-only');
-INSERT INTO vsc_cv.licenses(id, name) VALUES (7, 'AGPL-3.0-or-later');
-INSERT INTO vsc_cv.licenses(id, name) VALUES (8, 'MPL 2.0');
-INSERT INTO vsc_cv.licenses(id, name) VALUES (9, 'BSL-1.0');
-INSERT INTO vsc_cv.licenses(id, name) VALUES (10, 'WTFPL');
-INSERT INTO vsc_cv.licenses(id, name) VALUES (11, 'Unlicense');
+INSERT INTO vsc_cv.status(id, name) VALUES (6, 'cancelled');
 
 INSERT INTO vsc_cv.languages(id, name) VALUES (0, 'assemblyscript');
 INSERT INTO vsc_cv.languages(id, name) VALUES (1, 'golang');
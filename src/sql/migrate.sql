@@ -0,0 +1,100 @@
+-- Upgrades databases created by earlier versions of create_tables.sql, executed on every start.
+-- Every statement must be idempotent.
+
+ALTER TABLE vsc_cv.contracts ALTER COLUMN hive_username TYPE VARCHAR(68);
+ALTER TABLE vsc_cv.contracts ADD COLUMN IF NOT EXISTS abi jsonb;
+ALTER TABLE vsc_cv.contracts ADD COLUMN IF NOT EXISTS hidden BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE vsc_cv.source_code ADD COLUMN IF NOT EXISTS sha256 VARCHAR(64);
+-- same as bundle::file_hash, files uploaded before hashing was introduced would otherwise be left out of the Merkle root
+UPDATE vsc_cv.source_code SET sha256 = encode(sha256(convert_to(content, 'UTF8')), 'hex') WHERE sha256 IS NULL AND content IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS vsc_cv.verification_attempts(
+  id SERIAL PRIMARY KEY,
+  contract_addr VARCHAR(68) NOT NULL REFERENCES vsc_cv.contracts(contract_addr),
+  hive_username VARCHAR(68) NOT NULL,
+  request_ts TIMESTAMP NOT NULL,
+  completed_ts TIMESTAMP,
+  status SMALLINT NOT NULL REFERENCES vsc_cv.status(id),
+  license VARCHAR(255),
+  lang SMALLINT NOT NULL REFERENCES vsc_cv.languages(id),
+  dependencies jsonb,
+  sources jsonb,
+  logs VARCHAR
+);
+CREATE INDEX IF NOT EXISTS verification_attempts_contract_addr_idx ON vsc_cv.verification_attempts(contract_addr);
+
+CREATE TABLE IF NOT EXISTS vsc_cv.contract_metadata(
+  contract_addr VARCHAR(68) NOT NULL REFERENCES vsc_cv.contracts(contract_addr),
+  version INTEGER NOT NULL,
+  hive_username VARCHAR(68) NOT NULL,
+  ts TIMESTAMP NOT NULL,
+  metadata jsonb NOT NULL,
+  PRIMARY KEY(contract_addr, version)
+);
+
+CREATE TABLE IF NOT EXISTS vsc_cv.receipts(
+  contract_addr VARCHAR(68) PRIMARY KEY REFERENCES vsc_cv.contracts(contract_addr),
+  statement VARCHAR NOT NULL,
+  signature VARCHAR(128) NOT NULL,
+  key_id VARCHAR(16) NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS vsc_cv.attestations(
+  contract_addr VARCHAR(68) PRIMARY KEY REFERENCES vsc_cv.contracts(contract_addr),
+  status SMALLINT NOT NULL DEFAULT 0,
+  tx_id VARCHAR(40),
+  attempts SMALLINT NOT NULL DEFAULT 0,
+  last_error VARCHAR,
+  created_ts TIMESTAMP NOT NULL,
+  broadcast_ts TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS vsc_cv.login_challenges(
+  nonce VARCHAR(32) PRIMARY KEY,
+  account VARCHAR(68) NOT NULL,
+  expiry TIMESTAMP NOT NULL
+);
+-- challenges issued before they were bound to an account can no longer be used
+ALTER TABLE vsc_cv.login_challenges ADD COLUMN IF NOT EXISTS account VARCHAR(68) NOT NULL DEFAULT '';
+ALTER TABLE vsc_cv.login_challenges ALTER COLUMN account DROP DEFAULT;
+CREATE INDEX IF NOT EXISTS login_challenges_account_idx ON vsc_cv.login_challenges(account);
+
+CREATE TABLE IF NOT EXISTS vsc_cv.sessions(
+  id VARCHAR(32) PRIMARY KEY,
+  username VARCHAR(68) NOT NULL,
+  network VARCHAR(10) NOT NULL,
+  refresh_hash VARCHAR(64) NOT NULL,
+  created_ts TIMESTAMP NOT NULL,
+  last_refresh_ts TIMESTAMP NOT NULL,
+  expiry TIMESTAMP NOT NULL,
+  revoked BOOLEAN NOT NULL DEFAULT FALSE
+);
+CREATE INDEX IF NOT EXISTS sessions_username_idx ON vsc_cv.sessions(username);
+
+CREATE TABLE IF NOT EXISTS vsc_cv.admin_audit(
+  id SERIAL PRIMARY KEY,
+  username VARCHAR(68) NOT NULL,
+  action VARCHAR(30) NOT NULL,
+  target VARCHAR(68),
+  details jsonb,
+  ts TIMESTAMP NOT NULL
+);
+
+INSERT INTO vsc_cv.status(id, name) VALUES (6, 'cancelled') ON CONFLICT DO NOTHING;
+
+-- Licenses used to reference seeded SPDX rows by id, contracts now store SPDX expressions
+-- and the licenses table only holds custom LicenseRef- licenses.
+ALTER TABLE vsc_cv.licenses ALTER COLUMN name TYPE VARCHAR(64);
+ALTER TABLE vsc_cv.licenses ADD COLUMN IF NOT EXISTS text VARCHAR;
+DO $$
+BEGIN
+  IF (SELECT data_type FROM information_schema.columns WHERE table_schema = 'vsc_cv' AND table_name = 'contracts' AND column_name = 'license') = 'smallint' THEN
+    UPDATE vsc_cv.licenses SET name = 'MPL-2.0' WHERE name = 'MPL 2.0';
+    -- ALTER COLUMN ... USING does not allow subqueries
+    ALTER TABLE vsc_cv.contracts ADD COLUMN license_expr VARCHAR(255);
+    UPDATE vsc_cv.contracts c SET license_expr = l.name FROM vsc_cv.licenses l WHERE l.id = c.license;
+    ALTER TABLE vsc_cv.contracts DROP COLUMN license;
+    ALTER TABLE vsc_cv.contracts RENAME COLUMN license_expr TO license;
+    DELETE FROM vsc_cv.licenses WHERE name NOT LIKE 'LicenseRef-%';
+  END IF;
+END $$;
@@ -0,0 +1,234 @@
+use serde::{ Serialize, Deserialize };
+use serde_json::Value;
+use utoipa::ToSchema;
+use crate::types::{ cv::LatestMetadata, vsc::{ Contract, ElectionResultRecord, LedgerOpLog, Output, TransactionRecord, WitnessStat } };
+
+#[derive(Serialize, ToSchema)]
+pub struct Props {
+  pub last_processed_block: i64,
+  pub l2_block_height: u64,
+  pub witnesses: i32,
+  pub epoch: u64,
+  pub contracts: u64,
+  pub transactions: u64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ContractDetail {
+  #[serde(flatten)]
+  pub contract: Contract,
+  /// Latest verifier submitted metadata
+  pub metadata: Option<LatestMetadata>,
+  pub stats: ContractStats,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ContractStats {
+  pub calls: u64,
+  pub unique_callers: u64,
+  pub successful: u64,
+  pub failed: u64,
+  /// Calls not anchored yet
+  pub pending: u64,
+  /// Share of successful calls among anchored calls that succeeded or failed, unset without any
+  pub success_rate: Option<f64>,
+  pub failure_rate: Option<f64>,
+  pub last_call_height: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ContractCaller {
+  pub caller: String,
+  pub calls: u64,
+  pub successful: u64,
+  pub failed: u64,
+  pub first_height: Option<u64>,
+  pub last_height: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ContractActivity {
+  /// First block height of the interval
+  pub height: u64,
+  pub calls: u64,
+  pub unique_callers: u64,
+  pub successful: u64,
+  pub failed: u64,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum SearchValue {
+  Id(String),
+  Epoch(i64),
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SearchResult {
+  /// block, election, contract, tx or empty when nothing was found
+  #[serde(rename = "type")]
+  pub r#type: String,
+  pub result: SearchValue,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TxAnchor {
+  /// Hive block id
+  pub block: String,
+  /// Hive transaction id
+  pub tx_id: String,
+  pub height: u64,
+  /// Index of the transaction within the block
+  pub index: i64,
+  /// Index of the operation within the transaction
+  pub op_index: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AccountRcUsed {
+  pub account: String,
+  pub amount: u64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct L2TxOutput {
+  pub id: String,
+  pub status: String,
+  pub required_auths: Vec<String>,
+  pub nonce: Option<i64>,
+  /// Unset while the transaction is pending
+  pub anchor: Option<TxAnchor>,
+  pub ledger: Vec<LedgerOpLog>,
+  /// Contract output of contract calls
+  pub output: Option<Output>,
+  pub rc_limit: Option<u64>,
+  /// RCs used by each required auth as of the anchoring block
+  pub rc_used: Vec<AccountRcUsed>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum TxOutput {
+  /// Output of every operation of a Hive transaction, null for operations without one
+  #[schema(value_type = Vec<Option<Object>>)]
+  Hive(Vec<Option<Value>>),
+  L2(Box<L2TxOutput>),
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AccountHistoryEntry {
+  /// ledger_action, ledger_op or contract_call
+  pub kind: String,
+  /// Ledger action id or the id of the transaction containing the ledger op or contract call
+  pub id: String,
+  /// Index of the ledger op within the transaction
+  pub idx: i64,
+  pub height: u64,
+  /// Op type, i.e. transfer, withdraw, deposit or call
+  #[serde(rename = "type")]
+  pub r#type: Option<String>,
+  pub asset: Option<String>,
+  pub amount: Option<i64>,
+  pub from: Option<String>,
+  pub to: Option<String>,
+  pub memo: Option<String>,
+  pub status: Option<String>,
+  pub contract_id: Option<String>,
+  pub action: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AccountHistory {
+  pub entries: Vec<AccountHistoryEntry>,
+  /// Cursor of the next page, unset on the last page
+  pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TxList {
+  pub txs: Vec<TransactionRecord>,
+  /// Cursor of the next page, unset on the last page
+  pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BalancePoint {
+  pub block_height: u64,
+  pub hive: u64,
+  pub hbd: u64,
+  pub hbd_savings: u64,
+  pub hive_consensus: u64,
+  /// RCs used as of the latest RC record at or before the block height
+  pub rc_used: u64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct EpochParticipation {
+  pub epoch: i64,
+  /// Blocks produced in the epoch while the witness was a member
+  pub eligible: i32,
+  pub signed: i32,
+  /// Fraction of the eligible blocks signed in the epoch, 0 to 1
+  pub rate: f64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct WitnessStatsDetail {
+  #[serde(flatten)]
+  pub stats: WitnessStat,
+  /// Fraction of all eligible blocks signed by the witness, 0 to 1, unset if it was never eligible
+  pub participation_rate: Option<f64>,
+  /// Fraction of eligible blocks signed over the returned epochs, 0 to 1
+  pub uptime: Option<f64>,
+  /// Participation in the latest epochs the witness was a member of, latest first
+  pub epochs: Vec<EpochParticipation>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct QuorumEntry {
+  /// L2 block number or epoch
+  pub id: i64,
+  /// Block CID or election data CID
+  pub cid: String,
+  /// Slot height of the block or Hive block height of the election
+  pub height: u64,
+  pub voted_weight: u64,
+  pub eligible_weight: u64,
+  pub quorum_met: bool,
+  pub quorum_margin: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct WeightChange {
+  pub account: String,
+  pub previous: u64,
+  pub current: u64,
+  pub change: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ProtocolVersionChange {
+  pub previous: u64,
+  pub current: u64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct EpochDiff {
+  /// Unset for the first election
+  pub previous_epoch: Option<i64>,
+  pub joined: Vec<String>,
+  pub left: Vec<String>,
+  /// Weight changes of members of both elections
+  pub weight_changes: Vec<WeightChange>,
+  pub total_weight_change: i64,
+  /// Unset when the protocol version did not change
+  pub protocol_version: Option<ProtocolVersionChange>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct EpochDetail {
+  #[serde(flatten)]
+  pub election: ElectionResultRecord,
+  /// Changes from the previous election
+  pub diff: EpochDiff,
+}
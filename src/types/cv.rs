@@ -0,0 +1,211 @@
+use serde::{ Serialize, Deserialize };
+use serde_json::Value;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+use crate::{ abi::Abi, metadata::ContractMetadata };
+
+#[derive(Serialize, ToSchema)]
+pub struct LoginChallenge {
+  pub nonce: String,
+  pub expiry: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TokenResp {
+  pub access_token: String,
+  /// Single use token in the format `session_id.secret`, rotated on every refresh
+  pub refresh_token: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Jwks {
+  #[schema(value_type = Vec<Object>)]
+  pub keys: Vec<Value>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Session {
+  pub id: String,
+  pub network: String,
+  pub created_ts: String,
+  pub last_refresh_ts: String,
+  pub expiry: String,
+  /// Whether this is the session of the access token used for the request
+  pub current: bool,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ReqVerifyNew {
+  /// SPDX license expression
+  pub license: String,
+  pub lang: String,
+  /// Package name -> semver requirement
+  #[schema(value_type = HashMap<String, String>)]
+  pub dependencies: Value,
+}
+
+/// Multipart form of a single source file upload
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct UploadFile {
+  #[schema(format = Binary, value_type = String)]
+  pub file: Vec<u8>,
+  pub filename: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct LatestMetadata {
+  pub version: i32,
+  pub ts: String,
+  pub metadata: ContractMetadata,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ContractInfo {
+  pub address: String,
+  /// Bytecode CID
+  pub code: String,
+  pub username: String,
+  pub request_ts: String,
+  pub verified_ts: Option<String>,
+  pub status: String,
+  #[schema(value_type = Option<Vec<String>>)]
+  pub exports: Option<Value>,
+  pub files: Vec<String>,
+  pub lockfile: Option<String>,
+  pub license: Option<String>,
+  pub lang: String,
+  #[schema(value_type = HashMap<String, String>)]
+  pub dependencies: Value,
+  pub hidden: bool,
+  pub metadata: Option<LatestMetadata>,
+  #[schema(value_type = Option<Abi>)]
+  pub abi: Option<Value>,
+  /// Filename -> hex encoded SHA-256 of every file including the lockfile
+  pub hashes: BTreeMap<String, String>,
+  pub merkle_root: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Receipt {
+  /// JSON statement, the signature covers its exact bytes
+  pub statement: String,
+  pub signature: String,
+  pub key_id: String,
+  pub alg: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ReceiptPubKey {
+  pub alg: String,
+  pub key_id: String,
+  pub public_key: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Attestation {
+  /// pending, broadcasted or failed
+  pub status: String,
+  /// Id of the broadcasted transaction, or of the last failed attempt while pending
+  pub tx_id: Option<String>,
+  pub attempts: i16,
+  pub last_error: Option<String>,
+  pub created_ts: String,
+  pub broadcast_ts: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SourceFileHash {
+  pub name: String,
+  /// Hex SHA-256 of the contents
+  pub sha256: Option<String>,
+}
+
+/// Source filenames, with their hashes when requested.
+#[derive(Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum SourceFileList {
+  Names(Vec<String>),
+  Hashes(Vec<SourceFileHash>),
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SourceFile {
+  pub name: String,
+  pub content: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MetadataUpdated {
+  pub success: bool,
+  pub version: i32,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MetadataVersion {
+  pub version: i32,
+  pub username: String,
+  pub ts: String,
+  pub metadata: ContractMetadata,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MetadataVersionInfo {
+  pub version: i32,
+  pub username: String,
+  pub ts: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct VerificationAttempt {
+  pub id: i32,
+  pub username: String,
+  pub request_ts: String,
+  pub completed_ts: Option<String>,
+  pub status: String,
+  pub license: Option<String>,
+  pub lang: String,
+  #[schema(value_type = Option<HashMap<String, String>>)]
+  pub dependencies: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AttemptSource {
+  pub name: String,
+  pub is_lockfile: bool,
+  pub content: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct VerificationAttemptDetail {
+  pub id: i32,
+  pub address: String,
+  pub username: String,
+  pub request_ts: String,
+  pub completed_ts: Option<String>,
+  pub status: String,
+  pub license: Option<String>,
+  pub lang: String,
+  #[schema(value_type = Option<HashMap<String, String>>)]
+  pub dependencies: Option<Value>,
+  /// Snapshot of the submitted sources, empty for hidden contracts
+  #[schema(value_type = Vec<AttemptSource>)]
+  pub sources: Value,
+  pub logs: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BytecodeLookup {
+  pub address: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AuditEntry {
+  pub id: i32,
+  pub username: String,
+  pub action: String,
+  pub target: Option<String>,
+  #[schema(value_type = Option<Object>)]
+  pub details: Option<Value>,
+  pub ts: String,
+}
@@ -40,3 +40,21 @@ pub struct TxByHash<T> {
   pub transaction_json: Transaction<T>,
   pub timestamp: String,
 }
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Authority {
+  pub weight_threshold: u32,
+  pub account_auths: Vec<(String, u16)>,
+  pub key_auths: Vec<(String, u16)>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct AccountAuthorities {
+  pub name: String,
+  pub posting: Authority,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct FindAccounts {
+  pub accounts: Vec<AccountAuthorities>,
+}
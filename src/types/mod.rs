@@ -1,3 +1,5 @@
 pub mod hive;
 pub mod vsc;
 pub mod server;
+pub mod cv;
+pub mod be;
@@ -1,11 +1,36 @@
 use actix_web::{ http::{ header::ContentType, StatusCode }, HttpResponse };
 use derive_more::derive::{ Display, Error };
-use serde_json::json;
+use serde::Serialize;
+use utoipa::ToSchema;
 use reqwest;
 use log::error;
 use std::fmt;
 use crate::{ db::DbPool, mongo::MongoDB };
 use crate::compiler::Compiler;
+use crate::authority::AuthorityCache;
+use crate::graphql::VscSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct ErrorResp {
+  pub error: String,
+}
+
+impl ErrorResp {
+  pub fn new(error: &str) -> Self {
+    ErrorResp { error: error.to_string() }
+  }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SuccessResp {
+  pub success: bool,
+}
+
+impl SuccessResp {
+  pub fn ok() -> Self {
+    SuccessResp { success: true }
+  }
+}
 
 #[derive(Display, Error)]
 pub enum RespErr {
@@ -15,12 +40,17 @@ pub enum RespErr {
   #[display("Missing access token in authentication header")] TokenMissing,
   #[display("Access token expired")] TokenExpired,
   #[display("Access token is invalid")] TokenInvalid,
+  #[display("Session has been revoked")] TokenRevoked,
   #[display("Failed to make signature verification request")] SigVerifyReqFail,
   #[display("Failed to verify signature")] SigVerifyFail,
   #[display("Failed to check for recent block")] SigRecentBlkReqFail,
   #[display("Signature is too old")] SigTooOld,
   #[display("Block hash does not match the corresponding block number")] SigBhNotMatch,
+  #[display("Login challenge is invalid or already used")] ChallengeInvalid,
+  #[display("Login challenge expired")] ChallengeExpired,
+  #[display("Too many outstanding login challenges")] ChallengeLimit,
   #[display("Failed to generate access token")] TokenGenFail,
+  #[display("Insufficient permissions")] Forbidden,
   #[display("{msg}")] InternalErr {
     msg: String,
   },
@@ -46,7 +76,7 @@ impl actix_web::error::ResponseError for RespErr {
     }
     HttpResponse::build(self.status_code())
       .insert_header(ContentType::json())
-      .json(json!({ "error": self.to_string() }))
+      .json(ErrorResp { error: self.to_string() })
   }
 
   fn status_code(&self) -> StatusCode {
@@ -55,12 +85,17 @@ impl actix_web::error::ResponseError for RespErr {
       RespErr::TokenMissing => StatusCode::UNAUTHORIZED,
       RespErr::TokenExpired => StatusCode::UNAUTHORIZED,
       RespErr::TokenInvalid => StatusCode::UNAUTHORIZED,
+      RespErr::TokenRevoked => StatusCode::UNAUTHORIZED,
       RespErr::SigVerifyReqFail => StatusCode::INTERNAL_SERVER_ERROR,
       RespErr::SigVerifyFail => StatusCode::UNAUTHORIZED,
       RespErr::SigRecentBlkReqFail => StatusCode::INTERNAL_SERVER_ERROR,
       RespErr::SigTooOld => StatusCode::UNAUTHORIZED,
       RespErr::SigBhNotMatch => StatusCode::UNAUTHORIZED,
+      RespErr::ChallengeInvalid => StatusCode::UNAUTHORIZED,
+      RespErr::ChallengeExpired => StatusCode::UNAUTHORIZED,
+      RespErr::ChallengeLimit => StatusCode::TOO_MANY_REQUESTS,
       RespErr::TokenGenFail => StatusCode::INTERNAL_SERVER_ERROR,
+      RespErr::Forbidden => StatusCode::FORBIDDEN,
       RespErr::InternalErr { .. } => StatusCode::INTERNAL_SERVER_ERROR,
       RespErr::BadRequest { .. } => StatusCode::BAD_REQUEST,
     }
@@ -73,4 +108,6 @@ pub struct Context {
   pub vsc_db: MongoDB,
   pub compiler: Compiler,
   pub http_client: reqwest::Client,
+  pub authorities: AuthorityCache,
+  pub gql_schema: VscSchema,
 }
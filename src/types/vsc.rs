@@ -1,6 +1,8 @@
 use serde::{ Serialize, Deserialize };
 use serde_json::Value;
 use mongodb::bson;
+use utoipa::ToSchema;
+use async_graphql::SimpleObject;
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct HiveBlocksSyncState {
@@ -14,7 +16,8 @@ pub struct IndexerState {
   pub epoch: i32,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+#[graphql(name = "Balance")]
 pub struct LedgerBalance {
   pub account: String,
   pub block_height: u64,
@@ -42,7 +45,8 @@ pub struct LedgerActions {
   pub r#type: String,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+#[graphql(complex)]
 pub struct LedgerOpLog {
   pub to: String,
   pub from: String,
@@ -55,25 +59,29 @@ pub struct LedgerOpLog {
   pub bidx: u64,
   pub opidx: u64,
   pub blockheight: u64,
+  #[schema(value_type = Option<Object>)]
+  #[graphql(skip)]
   pub params: Option<Value>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct RcUsedAtHeight {
   pub block_height: u64,
   pub amount: u64,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+#[graphql(name = "DIDKey")]
 pub struct DIDKey {
   ct: String,
   t: String,
   key: String,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+#[graphql(name = "Witness", complex)]
 pub struct Witnesses {
-  account: String,
+  pub account: String,
   height: i64,
   did_keys: Vec<DIDKey>,
   enabled: bool,
@@ -88,7 +96,7 @@ pub struct Witnesses {
   version_id: String,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct WitnessStat {
   #[serde(rename = "_id")]
   pub proposer: String,
@@ -96,9 +104,21 @@ pub struct WitnessStat {
   pub election_count: Option<i32>,
   pub last_block: Option<i32>,
   pub last_epoch: Option<i32>,
+  /// Blocks produced while the witness was an epoch member
+  pub eligible_blocks: Option<i32>,
+  /// Blocks signed by the witness
+  pub signed_blocks: Option<i32>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
+pub struct WitnessParticipation {
+  pub account: String,
+  pub epoch: i64,
+  pub eligible: i32,
+  pub signed: i32,
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct Contract {
   pub id: String,
   pub code: String,
@@ -110,19 +130,21 @@ pub struct Contract {
   pub creation_height: i64,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct Signature {
   pub sig: String,
   pub bv: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, SimpleObject)]
+#[graphql(complex)]
 pub struct ElectionMember {
   pub key: String,
   pub account: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, SimpleObject)]
+#[graphql(name = "Epoch", complex)]
 pub struct ElectionResultRecord {
   pub epoch: i64,
   pub net_id: String,
@@ -139,20 +161,27 @@ pub struct ElectionResultRecord {
   pub be_info: Option<ElectionExt>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct ElectionExt {
   pub ts: String,
   pub signature: Option<Signature>,
   pub voted_weight: u64,
   pub eligible_weight: u64,
+  /// Accounts of the previous epoch members that signed the election
+  pub signers: Option<Vec<String>>,
+  /// Whether voted_weight reached 2/3 of eligible_weight
+  pub quorum_met: Option<bool>,
+  /// Voted weight above the 2/3 threshold, negative when the quorum was not met
+  pub quorum_margin: Option<i64>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, ToSchema, SimpleObject)]
 pub struct BlockStat {
   pub size: u64,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, ToSchema, SimpleObject)]
+#[graphql(name = "Block", complex)]
 pub struct BlockHeaderRecord {
   pub id: String,
   pub block: String,
@@ -168,16 +197,23 @@ pub struct BlockHeaderRecord {
   pub be_info: Option<BlockIndexed>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
 pub struct BlockIndexed {
   pub block_id: u32,
   pub epoch: u32,
   pub signature: Signature,
   pub voted_weight: u64,
   pub eligible_weight: u64,
+  /// Accounts of the epoch members that signed the block
+  pub signers: Option<Vec<String>>,
+  /// Whether voted_weight reached 2/3 of eligible_weight
+  pub quorum_met: Option<bool>,
+  /// Voted weight above the 2/3 threshold, negative when the quorum was not met
+  pub quorum_margin: Option<i64>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+#[graphql(name = "Transaction")]
 pub struct TransactionRecord {
   #[serde(rename = "id")]
   pub id: String,
@@ -187,24 +223,29 @@ pub struct TransactionRecord {
   pub nonce: Option<i64>,
   #[serde(rename = "rc_limit")]
   pub rc_limit: Option<u64>,
-  // pub data: Document,
-  // #[serde(rename = "anchr_block")]
-  // pub anchored_block: String,
-  // #[serde(rename = "anchr_id")]
-  // pub anchored_id: String,
-  // #[serde(rename = "anchr_index")]
-  // pub anchored_index: i64,
-  // #[serde(rename = "anchr_opidx")]
-  // pub anchored_opidx: i64,
-  // #[serde(rename = "anchr_height")]
-  // pub anchored_height: u64,
+  /// Transaction payload, i.e. `type`, `contract_id`, `action` and `payload` of contract calls
+  #[schema(value_type = Option<Object>)]
+  #[graphql(skip)]
+  pub data: Option<Value>,
+  /// Hive block id the transaction was included in, unset while pending
+  #[serde(rename = "anchr_block")]
+  pub anchored_block: Option<String>,
+  #[serde(rename = "anchr_id")]
+  pub anchored_id: Option<String>,
+  #[serde(rename = "anchr_index")]
+  pub anchored_index: Option<i64>,
+  #[serde(rename = "anchr_opidx")]
+  pub anchored_opidx: Option<i64>,
+  #[serde(rename = "anchr_height")]
+  pub anchored_height: Option<u64>,
   // #[serde(rename = "first_seen")]
   // pub first_seen: DateTime<Utc>,
   pub output: Option<Output>,
   pub ledger: Option<Vec<LedgerOpLog>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, ToSchema, SimpleObject)]
+#[graphql(name = "TransactionOutput")]
 pub struct Output {
   #[serde(rename = "id")]
   pub id: String,
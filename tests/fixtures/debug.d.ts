@@ -0,0 +1,64 @@
+declare namespace __AdaptedExports {
+  /**
+   * assembly/index/init
+   * @param owner `~lib/string/String`
+   * @returns `~lib/string/String`
+   */
+  export function init(owner: string): string;
+  /**
+   * assembly/index/transfer
+   * @param payload `~lib/string/String`
+   * @returns `~lib/string/String`
+   */
+  export function transfer(payload: string): string;
+  /**
+   * assembly/index/mint
+   * @param to `~lib/string/String`
+   * @param amount `i64`
+   * @param memo `~lib/string/String`
+   * @returns `bool`
+   */
+  export function mint(to: string, amount: bigint, memo?: string): boolean;
+  /**
+   * assembly/index/balances
+   * @param accounts `~lib/array/Array<~lib/string/String>`
+   * @returns `~lib/map/Map<~lib/string/String,i64>`
+   */
+  export function balances(accounts: Array<string>): Map<string, bigint>;
+  /**
+   * assembly/index/pause
+   */
+  export function pause(): void;
+  /**
+   * ~lib/rt/itcms/__new
+   * @param size `usize`
+   * @param id `u32`
+   * @returns `usize`
+   */
+  export function __new(size: number, id: number): number;
+  /**
+   * ~lib/rt/itcms/__pin
+   * @param ptr `usize`
+   * @returns `usize`
+   */
+  export function __pin(ptr: number): number;
+  /**
+   * ~lib/rt/itcms/__unpin
+   * @param ptr `usize`
+   */
+  export function __unpin(ptr: number): void;
+  /**
+   * ~lib/rt/itcms/__collect
+   */
+  export function __collect(): void;
+  /** ~lib/rt/__rtti_base */
+  export const __rtti_base: {
+    /** @type `usize` */
+    get value(): number
+  };
+}
+/** Instantiates the compiled WebAssembly module with the given imports. */
+export declare function instantiate(module: WebAssembly.Module, imports: {
+  env: unknown,
+  sdk: unknown,
+}): Promise<typeof __AdaptedExports>;
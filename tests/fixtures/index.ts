@@ -0,0 +1,38 @@
+import { JSON } from 'assemblyscript-json/assembly'
+import { JSON as JSONAS } from 'json-as/assembly'
+import { db, console } from '@vsc.eco/sdk/assembly'
+
+@json
+class TransferArgs {
+  to: string = ''
+  amount: i64 = 0
+}
+
+export function init(owner: string): string {
+  db.setObject('owner', owner)
+  return '{}'
+}
+
+export function transferFrom(payload: string): string {
+  return payload
+}
+
+export function transfer(payload: string): string {
+  const args = JSONAS.parse<TransferArgs>(payload)
+  db.setObject('last', args.to)
+  return '{}'
+}
+
+export function mint(to: string, amount: i64, memo: string = '{}'): bool {
+  console.log(`mint ${amount} to ${to}`)
+  const parsed = JSON.parse( memo )
+  return parsed.isObj
+}
+
+export function balances(accounts: Array<string>): Map<string, i64> {
+  return new Map<string, i64>()
+}
+
+export function pause(): void {
+  db.setObject('paused', 'true')
+}